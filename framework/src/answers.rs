@@ -0,0 +1,186 @@
+use crate::{config::Config, error::Error, traits::Evaluation};
+use arrayvec::ArrayVec;
+use std::{fmt, fs, io, path::PathBuf};
+
+/// How an answer compares to the recorded answer for the same part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong { expected: String },
+    New,
+}
+
+/// The known-good answers of a day, indexed by part.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Answers(Vec<Option<String>>);
+
+/// Returns where the answers of a day are stored, which mirrors the layout of
+/// the inputs of each year and profile.
+pub fn get_answers_path(config: &Config, profile: Option<&str>, year: u32, day_nr: u32) -> PathBuf {
+    let mut path = config.year_dir(&config.answers_dir, year);
+    if let Some(profile) = profile {
+        path.push(profile);
+    }
+    path.push(format!("day{:0>2}.txt", day_nr));
+    path
+}
+
+impl Answers {
+    /// Loads the recorded answers of a day, if there are any.
+    pub fn load(
+        config: &Config,
+        profile: Option<&str>,
+        year: u32,
+        day_nr: u32,
+    ) -> Result<Answers, Error> {
+        match fs::read_to_string(get_answers_path(config, profile, year, day_nr)) {
+            Ok(contents) => Answers::parse(&contents),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn save(
+        &self,
+        config: &Config,
+        profile: Option<&str>,
+        year: u32,
+        day_nr: u32,
+    ) -> Result<(), Error> {
+        let path = get_answers_path(config, profile, year, day_nr);
+        let mut dir_path = path.clone();
+        dir_path.pop();
+        fs::create_dir_all(dir_path)?;
+        fs::write(path, self.to_string())?;
+        Ok(())
+    }
+
+    /// Parses lines in the format `ptN = answer`, where line breaks and
+    /// backslashes in the answer are escaped.
+    pub fn parse(contents: &str) -> Result<Answers, Error> {
+        let mut answers = Answers::default();
+        for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+            let invalid_line = || Error::InvalidInputDyn(format!("invalid answer line: {}", line));
+            let mut split = line.splitn(2, " = ");
+            let part_nr = split
+                .next()
+                .and_then(|part| part.strip_prefix("pt"))
+                .and_then(|nr| nr.parse::<usize>().ok())
+                .filter(|&nr| nr > 0)
+                .ok_or_else(invalid_line)?;
+            let value =
+                unescape(split.next().ok_or_else(invalid_line)?).ok_or_else(invalid_line)?;
+            if answers.0.len() < part_nr {
+                answers.0.resize(part_nr, None);
+            }
+            answers.0[part_nr - 1] = Some(value);
+        }
+        Ok(answers)
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.0.get(index).and_then(|answer| answer.as_deref())
+    }
+
+    pub fn verdict(&self, index: usize, value: &str) -> Verdict {
+        match self.get(index) {
+            Some(expected) if expected == value => Verdict::Correct,
+            Some(expected) => Verdict::Wrong {
+                expected: expected.to_owned(),
+            },
+            None => Verdict::New,
+        }
+    }
+
+    /// Compares every successfully computed part against the recorded answers.
+    pub fn verdicts(&self, evaluation: &Evaluation) -> ArrayVec<[Option<Verdict>; 2]> {
+        evaluation
+            .parts
+            .iter()
+            .enumerate()
+            .map(|(index, part)| {
+                part.result
+                    .as_ref()
+                    .ok()
+                    .map(|value| self.verdict(index, value))
+            })
+            .collect()
+    }
+
+    pub fn set(&mut self, index: usize, value: String) {
+        if self.0.len() <= index {
+            self.0.resize(index + 1, None);
+        }
+        self.0[index] = Some(value);
+    }
+
+    /// Stores the answer of every successfully computed part, keeping the
+    /// previously recorded answer for parts that failed.
+    pub fn record(&mut self, evaluation: &Evaluation) {
+        for (index, part) in evaluation.parts.iter().enumerate() {
+            if let Ok(value) = &part.result {
+                self.set(index, value.clone());
+            }
+        }
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, answer) in self.0.iter().enumerate() {
+            if let Some(answer) = answer {
+                writeln!(f, "pt{} = {}", index + 1, escape(answer))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+pub(crate) fn unescape(value: &str) -> Option<String> {
+    let mut res = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next()? {
+                '\\' => res.push('\\'),
+                'n' => res.push('\n'),
+                _ => return None,
+            }
+        } else {
+            res.push(c);
+        }
+    }
+    Some(res)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let answers = Answers(vec![Some("123".to_owned()), Some("a\\b\n#.#".to_owned())]);
+        let contents = answers.to_string();
+        assert_eq!(contents, "pt1 = 123\npt2 = a\\\\b\\n#.#\n");
+        assert_eq!(Answers::parse(&contents).unwrap(), answers);
+    }
+
+    #[test]
+    fn verdicts() {
+        let answers = Answers::parse("pt2 = 5\n").unwrap();
+        assert_eq!(answers.verdict(0, "5"), Verdict::New);
+        assert_eq!(answers.verdict(1, "5"), Verdict::Correct);
+        assert_eq!(
+            answers.verdict(1, "6"),
+            Verdict::Wrong {
+                expected: "5".to_owned()
+            }
+        );
+        assert!(Answers::parse("pt0 = 5").is_err());
+        assert!(Answers::parse("pt1 5").is_err());
+    }
+}
@@ -0,0 +1,297 @@
+use crate::{
+    config::Config,
+    error::Error,
+    report::{day_label, format_duration},
+    traits::Evaluation,
+};
+use colored::Colorize;
+use std::{fmt, fs, io, path::PathBuf, time::Duration};
+
+/// The change in percent beyond which a phase is highlighted as slower or
+/// faster, unless another one is passed using `--threshold`.
+pub const DEFAULT_THRESHOLD: f64 = 10.0;
+
+/// Identifies a timed phase of a day. Phases are named by their position, as
+/// `parse`, `pt1`, `pt2`, along with `total`, so that renaming the functions of
+/// a day does not invalidate a baseline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Key {
+    pub year: u32,
+    pub day_nr: u32,
+    pub profile: Option<String>,
+    pub phase: String,
+}
+
+/// The durations of every successful phase of the evaluated days, in the order
+/// they were evaluated in. Stored as lines in the format
+/// `2020 day07 pt1 = nanoseconds`, with the profile after the day for inputs of
+/// profiles, such as `2020 day07 team alice pt1 = nanoseconds`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Baseline(Vec<(Key, Duration)>);
+
+/// How the duration of a phase compares to that in a baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Delta {
+    pub key: Key,
+    pub baseline: Duration,
+    pub current: Duration,
+    /// The change in percent, where positive means slower.
+    pub change: f64,
+}
+
+pub fn get_baseline_path(config: &Config, name: &str) -> PathBuf {
+    config.baseline_dir.join(format!("{}.txt", name))
+}
+
+impl Baseline {
+    /// Loads a baseline, which must exist.
+    pub fn load(config: &Config, name: &str) -> Result<Baseline, Error> {
+        let path = get_baseline_path(config, name);
+        match fs::read_to_string(&path) {
+            Ok(contents) => Baseline::parse(&contents),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Err(Error::InvalidArgument(
+                format!("no baseline named {} at {}", name, path.display()),
+            )),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn save(&self, config: &Config, name: &str) -> Result<(), Error> {
+        fs::create_dir_all(&config.baseline_dir)?;
+        fs::write(get_baseline_path(config, name), self.to_string())?;
+        Ok(())
+    }
+
+    pub fn parse(contents: &str) -> Result<Baseline, Error> {
+        let mut baseline = Baseline::default();
+        for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+            let invalid_line =
+                || Error::InvalidInputDyn(format!("invalid baseline line: {}", line));
+            // The profile is everything between the day and the phase, as the
+            // names of profiles may contain spaces.
+            let mut split = line.rsplitn(2, " = ");
+            let nanos = split.next().and_then(|nanos| nanos.parse().ok());
+            let mut fields = split.next().ok_or_else(invalid_line)?.splitn(3, ' ');
+            let (year, day, rest) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(year), Some(day), Some(rest)) => (year, day, rest),
+                _ => return Err(invalid_line()),
+            };
+            let mut rest = rest.rsplitn(2, ' ');
+            let phase = rest
+                .next()
+                .filter(|phase| !phase.is_empty())
+                .ok_or_else(invalid_line)?;
+            let profile = rest.next().map(str::to_owned);
+            let year = year.parse().ok();
+            let day_nr = day.strip_prefix("day").and_then(|nr| nr.parse().ok());
+            match (year, day_nr, nanos) {
+                (Some(year), Some(day_nr), Some(nanos)) => baseline.0.push((
+                    Key {
+                        year,
+                        day_nr,
+                        profile,
+                        phase: phase.to_owned(),
+                    },
+                    Duration::from_nanos(nanos),
+                )),
+                _ => return Err(invalid_line()),
+            }
+        }
+        Ok(baseline)
+    }
+
+    /// Adds the durations of the successful phases of an evaluation. The total
+    /// is only added when every phase succeeded.
+    pub fn add(&mut self, year: u32, day_nr: u32, profile: Option<&str>, evaluation: &Evaluation) {
+        let mut add = |phase: String, duration| {
+            let key = Key {
+                year,
+                day_nr,
+                profile: profile.map(str::to_owned),
+                phase,
+            };
+            self.0.push((key, duration));
+        };
+        if evaluation.parse.result.is_err() {
+            return;
+        }
+        add("parse".to_owned(), evaluation.parse.duration);
+        for (index, part) in evaluation.parts.iter().enumerate() {
+            if part.result.is_ok() {
+                add(format!("pt{}", index + 1), part.duration);
+            }
+        }
+        if evaluation.parts.iter().all(|part| part.result.is_ok()) {
+            add("total".to_owned(), evaluation.total_duration());
+        }
+    }
+
+    pub fn get(&self, key: &Key) -> Option<Duration> {
+        self.0
+            .iter()
+            .find(|(other, _)| other == key)
+            .map(|&(_, duration)| duration)
+    }
+
+    /// Lists the phases of a baseline that are missing from these timings, such
+    /// as phases that failed, limited to the days that `is_evaluated` accepts.
+    pub fn missing(
+        &self,
+        baseline: &Baseline,
+        is_evaluated: impl Fn(&Key) -> bool,
+    ) -> Vec<(Key, Duration)> {
+        baseline
+            .0
+            .iter()
+            .filter(|(key, _)| is_evaluated(key) && self.get(key).is_none())
+            .cloned()
+            .collect()
+    }
+
+    /// Compares every phase against the same phase in a baseline, skipping
+    /// those that are not in it.
+    pub fn compare(&self, baseline: &Baseline) -> Vec<Delta> {
+        self.0
+            .iter()
+            .filter_map(|(key, current)| {
+                let previous = baseline.get(key)?;
+                let change = if previous == Duration::from_secs(0) {
+                    0.0
+                } else {
+                    (current.as_secs_f64() / previous.as_secs_f64() - 1.0) * 100.0
+                };
+                Some(Delta {
+                    key: key.clone(),
+                    baseline: previous,
+                    current: *current,
+                    change,
+                })
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, duration) in &self.0 {
+            write!(f, "{} day{:0>2} ", key.year, key.day_nr)?;
+            if let Some(profile) = &key.profile {
+                write!(f, "{} ", profile)?;
+            }
+            writeln!(f, "{} = {}", key.phase, duration.as_nanos())?;
+        }
+        Ok(())
+    }
+}
+
+/// Prints the change of every phase, highlighting those that changed by more
+/// than the threshold in percent, followed by the phases that are missing, and
+/// the number of such phases.
+pub fn print_comparison(
+    name: &str,
+    deltas: &[Delta],
+    missing: &[(Key, Duration)],
+    threshold: f64,
+    default_year: u32,
+) {
+    println!(
+        "{}",
+        format!("compared to baseline {}", name).bright_white()
+    );
+    let label = |key: &Key| {
+        let mut label = day_label(key.year, default_year, key.day_nr);
+        if let Some(profile) = &key.profile {
+            label = format!("{} [{}]", label, profile);
+        }
+        label
+    };
+    let (mut slower, mut faster) = (0, 0);
+    for delta in deltas {
+        let change = format!("{:+.1}%", delta.change);
+        let change = if delta.change > threshold {
+            slower += 1;
+            change.bright_red().bold()
+        } else if delta.change < -threshold {
+            faster += 1;
+            change.bright_green()
+        } else {
+            change.normal()
+        };
+        println!(
+            "{} {: <5} {} ms -> {} ms {: >8}",
+            label(&delta.key).bright_blue(),
+            delta.key.phase,
+            format_duration(delta.baseline),
+            format_duration(delta.current),
+            change
+        );
+    }
+    for (key, duration) in missing {
+        println!(
+            "{} {: <5} {} ms -> {}",
+            label(key).bright_blue(),
+            key.phase,
+            format_duration(*duration),
+            "missing".bright_red().bold()
+        );
+    }
+    println!(
+        "{} phase(s) slower and {} faster by more than {}%, {} missing",
+        slower,
+        faster,
+        threshold,
+        missing.len()
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn baselines() {
+        let contents =
+            "2020 day07 parse = 1500\n2020 day07 pt1 = 2000\n2019 day05 team alice pt2 = 10\n";
+        let baseline = Baseline::parse(contents).unwrap();
+        assert_eq!(baseline.to_string(), contents);
+        assert_eq!(
+            baseline.get(&Key {
+                year: 2019,
+                day_nr: 5,
+                profile: Some("team alice".to_owned()),
+                phase: "pt2".to_owned(),
+            }),
+            Some(Duration::from_nanos(10))
+        );
+        assert!(Baseline::parse("2020 day07 pt1 = 1.5").is_err());
+        assert!(Baseline::parse("2020 pt1 = 15").is_err());
+        assert!(Baseline::parse("2020 day07  = 15").is_err());
+    }
+
+    #[test]
+    fn comparisons() {
+        let baseline = Baseline::parse("2020 day07 parse = 1000\n2020 day07 pt1 = 2000\n").unwrap();
+        let current = Baseline::parse(
+            "2020 day07 parse = 1500\n2020 day07 pt1 = 1000\n2020 day07 pt2 = 10\n",
+        )
+        .unwrap();
+        let changes = current
+            .compare(&baseline)
+            .into_iter()
+            .map(|delta| (delta.key.phase, delta.change))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![("parse".to_owned(), 50.0), ("pt1".to_owned(), -50.0)]
+        );
+
+        let current = Baseline::parse("2020 day07 parse = 1500\n").unwrap();
+        let missing = current
+            .missing(&baseline, |key| key.day_nr == 7)
+            .into_iter()
+            .map(|(key, _)| key.phase)
+            .collect::<Vec<_>>();
+        assert_eq!(missing, vec!["pt1".to_owned()]);
+        assert!(current.missing(&baseline, |_| false).is_empty());
+    }
+}
@@ -0,0 +1,148 @@
+use crate::traits::{Day, Evaluation};
+use std::time::Duration;
+
+/// Summary of the durations measured over repeated runs of a day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Statistics {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Statistics {
+    pub fn from_samples(samples: &mut [Duration]) -> Option<Statistics> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort_unstable();
+
+        let runs = samples.len();
+        let median = if runs % 2 == 0 {
+            (samples[runs / 2 - 1] + samples[runs / 2]) / 2
+        } else {
+            samples[runs / 2]
+        };
+        let mean = samples.iter().map(Duration::as_secs_f64).sum::<f64>() / runs as f64;
+        let variance = if runs > 1 {
+            samples
+                .iter()
+                .map(|sample| (sample.as_secs_f64() - mean).powi(2))
+                .sum::<f64>()
+                / (runs - 1) as f64
+        } else {
+            0.0
+        };
+
+        Some(Statistics {
+            runs,
+            min: samples[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+/// Evaluates something `runs` times, preceded by a number of untimed warmup
+/// runs if `warmup` is set. Returns the outcome of the last run, with
+/// every duration listed by `durations` replaced by its median, along with the
+/// sum of those durations for every run.
+pub fn run_repeatedly<T>(
+    runs: usize,
+    warmup: bool,
+    mut evaluate: impl FnMut() -> T,
+    mut durations: impl FnMut(&mut T) -> Vec<&mut Duration>,
+) -> (T, Vec<Duration>) {
+    if warmup {
+        for _ in 0..(runs / 10).max(1) {
+            evaluate();
+        }
+    }
+
+    let mut totals = Vec::with_capacity(runs);
+    let mut samples = Vec::<Vec<Duration>>::new();
+    let mut outcome = None;
+    for _ in 0..runs {
+        let mut run_outcome = evaluate();
+        let run_durations = durations(&mut run_outcome);
+        totals.push(run_durations.iter().map(|duration| **duration).sum());
+        samples.resize_with(samples.len().max(run_durations.len()), Vec::new);
+        for (duration, duration_samples) in run_durations.into_iter().zip(&mut samples) {
+            duration_samples.push(*duration);
+        }
+        outcome = Some(run_outcome);
+    }
+
+    let mut outcome = outcome.expect("no runs");
+    let medians = samples
+        .iter_mut()
+        .map(|samples| Statistics::from_samples(samples).unwrap().median);
+    for (duration, median) in durations(&mut outcome).into_iter().zip(medians) {
+        *duration = median;
+    }
+    (outcome, totals)
+}
+
+/// Evaluates a day `runs` times, as described by [`run_repeatedly`], and
+/// returns statistics for the total duration along with the evaluation.
+pub fn run(day: &dyn Day, input: &str, runs: usize) -> (Evaluation, Statistics) {
+    let (evaluation, mut totals) = run_repeatedly(
+        runs,
+        true,
+        || day.evaluate(input.to_owned()),
+        |evaluation| {
+            let mut durations = vec![&mut evaluation.parse.duration];
+            durations.extend(evaluation.parts.iter_mut().map(|part| &mut part.duration));
+            durations
+        },
+    );
+    (evaluation, Statistics::from_samples(&mut totals).unwrap())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn statistics() {
+        let ms = Duration::from_millis;
+        assert_eq!(Statistics::from_samples(&mut []), None);
+        assert_eq!(
+            Statistics::from_samples(&mut [ms(4), ms(1), ms(3), ms(2)]),
+            Some(Statistics {
+                runs: 4,
+                min: ms(1),
+                median: Duration::from_micros(2500),
+                mean: Duration::from_micros(2500),
+                stddev: Duration::from_secs_f64(0.001290994448735806),
+            })
+        );
+        assert_eq!(
+            Statistics::from_samples(&mut [ms(5)]).map(|stats| (stats.median, stats.stddev)),
+            Some((ms(5), Duration::from_secs(0)))
+        );
+    }
+
+    #[test]
+    fn medians() {
+        let ms = Duration::from_millis;
+        // The first run is a warmup run, which is not timed.
+        let mut samples = vec![
+            (ms(8), ms(8)),
+            (ms(1), ms(9)),
+            (ms(5), ms(2)),
+            (ms(3), ms(4)),
+        ]
+        .into_iter();
+        let (outcome, totals) = run_repeatedly(
+            3,
+            true,
+            || samples.next().unwrap_or_default(),
+            |(first, second)| vec![first, second],
+        );
+        assert_eq!(outcome, (ms(3), ms(4)));
+        assert_eq!(totals, vec![ms(10), ms(7), ms(7)]);
+    }
+}
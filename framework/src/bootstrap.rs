@@ -1,57 +1,176 @@
+/// Declares the modules of the days and the `main` function running them.
+/// Days listed directly belong to the default year, and are declared as
+/// `src/dayXX.rs`. Days of other years are grouped by year, and declared as
+/// `src/yearXXXX/dayXX.rs`:
+///
+/// ```text
+/// framework::main! {
+///     day01
+///     day02
+///     2019 {
+///         day05
+///     }
+/// }
+/// ```
 #[macro_export]
 macro_rules! main {
-    ($($day:ident)+) => {
-        $(mod $day;)+
+    (
+        $($day:ident)*
+        $($year:literal { $($year_day:ident)* })*
+    ) => {
+        $(mod $day;)*
+        $(
+            $crate::paste! {
+                mod [<year $year>] {
+                    $(pub mod $year_day;)*
+                }
+            }
+        )*
+
+        $crate::global_allocator!();
 
-        pub fn main() -> ::std::result::Result<(), $crate::error::Error> {
-            $crate::run(&[
-                $((stringify!($day), $day::DAY_SPEC),)+
-            ])
+        pub fn main() {
+            if let Err(err) = $crate::run(&[
+                $((None, stringify!($day), $day::DAY_SPEC),)*
+                $($(
+                    (
+                        Some($year),
+                        stringify!($year_day),
+                        $crate::paste!([<year $year>]::$year_day::DAY_SPEC),
+                    ),
+                )*)*
+            ]) {
+                eprintln!("error: {}", err);
+                ::std::process::exit(1);
+            }
         }
     };
 }
 
+/// Declares a day, given its number, its parser, and its parts. Every part can
+/// also list alternative implementations, which must give the same answer. The
+/// first one is the one that is normally used, and `--variants` runs them all:
+///
+/// ```text
+/// day!(23, parse => pt1 = [pt1, pt1_original], pt2);
+/// ```
 #[macro_export]
 macro_rules! day {
+    ($day:literal, $parser:ident => $pt1:ident = [$($pt1_variant:ident),+ $(,)?], $pt2:ident = [$($pt2_variant:ident),+ $(,)?]) => {
+        $crate::day!(@day $day, $parser => $pt1 [$($pt1_variant),+], $pt2 [$($pt2_variant),+]);
+    };
+    ($day:literal, $parser:ident => $pt1:ident = [$($pt1_variant:ident),+ $(,)?], $pt2:ident) => {
+        $crate::day!(@day $day, $parser => $pt1 [$($pt1_variant),+], $pt2 [$pt2]);
+    };
+    ($day:literal, $parser:ident => $pt1:ident, $pt2:ident = [$($pt2_variant:ident),+ $(,)?]) => {
+        $crate::day!(@day $day, $parser => $pt1 [$pt1], $pt2 [$($pt2_variant),+]);
+    };
     ($day:literal, $parser:ident => $pt1:ident, $pt2:ident) => {
+        $crate::day!(@day $day, $parser => $pt1 [$pt1], $pt2 [$pt2]);
+    };
+    (
+        @day $day:literal, $parser:ident =>
+        $pt1:ident [$pt1_main:ident $(, $pt1_alternative:ident)*],
+        $pt2:ident [$pt2_main:ident $(, $pt2_alternative:ident)*]
+    ) => {
         struct DayStruct;
-        impl framework::traits::Day for DayStruct {
-            fn nr(&self) -> u32 {
-                $day
-            }
-
+        impl DayStruct {
+            /// Evaluates the parser and the main implementation of every part,
+            /// followed by the alternatives if requested. Parts are named after
+            /// their implementation when evaluating the alternatives. Stops as
+            /// soon as `report` returns `false`.
             #[allow(unreachable_code)]
-            fn evaluate(
+            fn evaluate_with(
                 &self,
                 input: String,
-            ) -> arrayvec::ArrayVec<[(&'static str, Result<String>); 2]> {
-                use framework::traits::{IntoError, IntoResult};
-                let mut res = arrayvec::ArrayVec::<[(&'static str, Result<String>); 2]>::new();
-                let input = match $parser(&input).into_result() {
-                    Ok(v) => v,
+                alternatives: bool,
+                report: &mut dyn FnMut($crate::traits::PhaseResult) -> bool,
+            ) {
+                use $crate::traits::{run_phase, IntoResult, Phase, PhaseResult};
+                let (input, duration, allocations) = run_phase(|| $parser(&input).into_result());
+                let input = match input {
+                    Ok(v) => {
+                        if !report(PhaseResult::Parse(Phase {
+                            name: stringify!($parser),
+                            result: Ok(()),
+                            duration,
+                            allocations,
+                        })) {
+                            return;
+                        }
+                        v
+                    }
                     Err(err) => {
-                        res.push((stringify!($parser), Err(err)));
-                        return res;
+                        report(PhaseResult::Parse(Phase {
+                            name: stringify!($parser),
+                            result: Err(err),
+                            duration,
+                            allocations,
+                        }));
+                        return;
                     }
                 };
 
-                res.push((
-                    stringify!($pt1),
-                    $pt1(&input)
-                        .into_result()
-                        .map(|x| x.to_string())
-                        .map_err(|x| x.into_error()),
-                ));
-                res.push((
-                    stringify!($pt2),
-                    $pt2(&input)
-                        .into_result()
-                        .map(|x| x.to_string())
-                        .map_err(|x| x.into_error()),
-                ));
-                res
+                let name = if alternatives { stringify!($pt1_main) } else { stringify!($pt1) };
+                $crate::day!(@part report, input, name, $pt1_main);
+                if alternatives {
+                    $($crate::day!(@part report, input, stringify!($pt1_alternative), $pt1_alternative);)*
+                }
+                let name = if alternatives { stringify!($pt2_main) } else { stringify!($pt2) };
+                $crate::day!(@part report, input, name, $pt2_main);
+                if alternatives {
+                    $($crate::day!(@part report, input, stringify!($pt2_alternative), $pt2_alternative);)*
+                }
             }
         }
-        pub const DAY_SPEC: &'static dyn framework::traits::Day = &DayStruct;
+
+        impl $crate::traits::Day for DayStruct {
+            fn nr(&self) -> u32 {
+                $day
+            }
+
+            fn phase_names(&self) -> &'static [&'static str] {
+                &[stringify!($parser), stringify!($pt1), stringify!($pt2)]
+            }
+
+            fn variant_names(&self) -> [&'static [&'static str]; 2] {
+                [
+                    &[stringify!($pt1_main) $(, stringify!($pt1_alternative))*],
+                    &[stringify!($pt2_main) $(, stringify!($pt2_alternative))*],
+                ]
+            }
+
+            fn evaluate_phases(
+                &self,
+                input: String,
+                report: &mut dyn FnMut($crate::traits::PhaseResult) -> bool,
+            ) {
+                self.evaluate_with(input, false, report);
+            }
+
+            fn evaluate_variants(
+                &self,
+                input: String,
+                report: &mut dyn FnMut($crate::traits::PhaseResult) -> bool,
+            ) {
+                self.evaluate_with(input, true, report);
+            }
+        }
+        pub const DAY_SPEC: &'static dyn $crate::traits::Day = &DayStruct;
+    };
+    (@part $report:ident, $input:ident, $name:expr, $function:ident) => {
+        let (result, duration, allocations) = $crate::traits::run_phase(|| {
+            $crate::traits::IntoResult::into_result($function(&$input))
+                .map(|x| x.to_string())
+                .map_err($crate::traits::IntoError::into_error)
+        });
+        if !$report($crate::traits::PhaseResult::Part($crate::traits::Phase {
+            name: $name,
+            result,
+            duration,
+            allocations,
+        })) {
+            return;
+        }
     };
 }
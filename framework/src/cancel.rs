@@ -0,0 +1,55 @@
+//! Cooperative cancellation of long-running parts.
+//!
+//! When a part exceeds the timeout, the runner flags it as cancelled. Solvers
+//! with loops that may not terminate on bad inputs can poll for this, and bail
+//! out with `Error::Cancelled`.
+
+use crate::error::Error;
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+thread_local! {
+    static TOKEN: RefCell<Option<Arc<AtomicBool>>> = RefCell::new(None);
+}
+
+/// Sets the token which indicates whether the phase currently running on this
+/// thread has been cancelled.
+pub(crate) fn set_token(token: Option<Arc<AtomicBool>>) {
+    TOKEN.with(|cell| *cell.borrow_mut() = token);
+}
+
+pub fn is_cancelled() -> bool {
+    TOKEN.with(|cell| {
+        cell.borrow()
+            .as_ref()
+            .map_or(false, |token| token.load(Ordering::Relaxed))
+    })
+}
+
+/// Returns `Error::Cancelled` if the current phase has been cancelled.
+pub fn check() -> Result<(), Error> {
+    if is_cancelled() {
+        Err(Error::Cancelled)
+    } else {
+        Ok(())
+    }
+}
+
+/// How many loop iterations `check_every` lets pass between checks.
+const CHECK_INTERVAL: usize = 1 << 16;
+
+/// Like `check`, but only actually checks once every so many iterations, so
+/// that it can be called on every iteration of a hot loop.
+#[inline]
+pub fn check_every(iteration: usize) -> Result<(), Error> {
+    if iteration % CHECK_INTERVAL == 0 {
+        check()
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,262 @@
+//! Configuration of the runner.
+//!
+//! Every setting is resolved in the following order, where the first one that
+//! is present wins:
+//!
+//! 1. An environment variable (`AOC_SESSION`, `AOC_INPUT_DIR`).
+//! 2. The `aoc.toml` file. It is looked up at the path in `AOC_CONFIG`, or else
+//!    in the current directory and its ancestors.
+//! 3. The defaults.
+//!
+//! Relative paths in `aoc.toml`, and the default paths, are relative to the
+//! directory containing `aoc.toml`, or the current directory if there is none.
+//!
+//! ```toml
+//! year = 2020                 # the default year of the puzzles
+//! session_file = "token.txt"  # file containing the session cookie
+//! input_dir = "inputs"        # where inputs are cached
+//! answers_dir = "answers"     # where known-good answers are stored
+//! examples_dir = "examples"   # where puzzle examples are extracted to
+//! source_dir = "src"          # where new day modules are created
+//! baseline_dir = "baselines"  # where timing baselines are stored
+//! readme_file = "README.md"   # whose results table --update-readme updates
+//! trim_trailing_whitespace = false  # whether to trim the lines of inputs
+//! base_url = "https://adventofcode.com"
+//! ```
+//!
+//! The inputs, answers and examples of the default year are stored directly in
+//! their directories, and those of other years in a subdirectory named after
+//! the year, such as `inputs/2019/day05.txt`.
+//!
+//! The session cookie can also be set directly using `session = "..."`, but
+//! prefer `AOC_SESSION` to keep it out of the repository.
+
+use crate::error::Error;
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+pub const CONFIG_FILE_NAME: &str = "aoc.toml";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub year: u32,
+    /// The session cookie, if it is set directly.
+    pub session: Option<String>,
+    /// The file to read the session cookie from, if it is not set directly.
+    pub session_file: PathBuf,
+    pub input_dir: PathBuf,
+    pub answers_dir: PathBuf,
+    pub examples_dir: PathBuf,
+    /// The directory containing `main.rs` and the modules of the days.
+    pub source_dir: PathBuf,
+    /// Where the timings stored by `--save-baseline` are kept.
+    pub baseline_dir: PathBuf,
+    /// The file containing the results table updated by `--update-readme`.
+    pub readme_file: PathBuf,
+    /// The address of the server, without a trailing slash.
+    pub base_url: String,
+    /// Whether to remove trailing whitespace from every line of the inputs.
+    pub trim_trailing_whitespace: bool,
+}
+
+impl Config {
+    pub(crate) fn with_base_dir(base_dir: &Path) -> Config {
+        Config {
+            year: 2020,
+            session: None,
+            session_file: base_dir.join("token.txt"),
+            input_dir: base_dir.join("inputs"),
+            answers_dir: base_dir.join("answers"),
+            examples_dir: base_dir.join("examples"),
+            source_dir: base_dir.join("src"),
+            baseline_dir: base_dir.join("baselines"),
+            readme_file: base_dir.join("README.md"),
+            base_url: "https://adventofcode.com".to_owned(),
+            trim_trailing_whitespace: false,
+        }
+    }
+
+    /// Returns the directory within `dir` that holds the files of a year.
+    pub fn year_dir(&self, dir: &Path, year: u32) -> PathBuf {
+        if year == self.year {
+            dir.to_owned()
+        } else {
+            dir.join(year.to_string())
+        }
+    }
+
+    /// Loads the configuration from `aoc.toml` and the environment.
+    pub fn load() -> Result<Config, Error> {
+        let mut config = match find_config_file()? {
+            Some(path) => {
+                let contents = fs::read_to_string(&path)?;
+                let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+                Config::parse(&contents, base_dir)?
+            }
+            None => Config::with_base_dir(Path::new("")),
+        };
+
+        if let Some(session) = env::var("AOC_SESSION").ok().filter(|s| !s.is_empty()) {
+            config.session = Some(session);
+        }
+        if let Some(input_dir) = env::var_os("AOC_INPUT_DIR").filter(|s| !s.is_empty()) {
+            config.input_dir = PathBuf::from(input_dir);
+        }
+        Ok(config)
+    }
+
+    /// Parses the contents of `aoc.toml`.
+    pub fn parse(contents: &str, base_dir: &Path) -> Result<Config, Error> {
+        let file: ConfigFile = toml::from_str(contents)
+            .map_err(|err| Error::InvalidConfig(CONFIG_FILE_NAME, err.to_string()))?;
+        if let Some(key) = file.unknown.keys().next() {
+            return Err(Error::InvalidConfig(
+                CONFIG_FILE_NAME,
+                format!("unknown key `{}`", key),
+            ));
+        }
+
+        let mut config = Config::with_base_dir(base_dir);
+        if let Some(year) = file.year {
+            config.year = year;
+        }
+        if file.session.is_some() {
+            config.session = file.session;
+        }
+        if let Some(base_url) = file.base_url {
+            config.base_url = base_url.trim_end_matches('/').to_owned();
+        }
+        if let Some(trim_trailing_whitespace) = file.trim_trailing_whitespace {
+            config.trim_trailing_whitespace = trim_trailing_whitespace;
+        }
+        for (path, setting) in [
+            (file.session_file, &mut config.session_file),
+            (file.input_dir, &mut config.input_dir),
+            (file.answers_dir, &mut config.answers_dir),
+            (file.examples_dir, &mut config.examples_dir),
+            (file.source_dir, &mut config.source_dir),
+            (file.baseline_dir, &mut config.baseline_dir),
+            (file.readme_file, &mut config.readme_file),
+        ]
+        .iter_mut()
+        {
+            if let Some(path) = path.take() {
+                **setting = base_dir.join(path);
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// The settings in `aoc.toml`, which are all optional.
+#[derive(Debug, Deserialize)]
+struct ConfigFile {
+    year: Option<u32>,
+    session: Option<String>,
+    session_file: Option<PathBuf>,
+    input_dir: Option<PathBuf>,
+    answers_dir: Option<PathBuf>,
+    examples_dir: Option<PathBuf>,
+    source_dir: Option<PathBuf>,
+    baseline_dir: Option<PathBuf>,
+    readme_file: Option<PathBuf>,
+    base_url: Option<String>,
+    trim_trailing_whitespace: Option<bool>,
+    /// Any other keys, which are rejected. Unlike `deny_unknown_fields`, this
+    /// does not report them at the start of the file.
+    #[serde(flatten)]
+    unknown: BTreeMap<String, toml::Value>,
+}
+
+fn find_config_file() -> Result<Option<PathBuf>, Error> {
+    if let Some(path) = env::var_os("AOC_CONFIG").filter(|s| !s.is_empty()) {
+        return Ok(Some(PathBuf::from(path)));
+    }
+    let current_dir = env::current_dir()?;
+    Ok(current_dir
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+        .find(|path| path.is_file()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let base_dir = Path::new("/aoc");
+        assert_eq!(
+            Config::parse("", base_dir).unwrap(),
+            Config {
+                year: 2020,
+                session: None,
+                session_file: PathBuf::from("/aoc/token.txt"),
+                input_dir: PathBuf::from("/aoc/inputs"),
+                answers_dir: PathBuf::from("/aoc/answers"),
+                examples_dir: PathBuf::from("/aoc/examples"),
+                source_dir: PathBuf::from("/aoc/src"),
+                baseline_dir: PathBuf::from("/aoc/baselines"),
+                readme_file: PathBuf::from("/aoc/README.md"),
+                base_url: "https://adventofcode.com".to_owned(),
+                trim_trailing_whitespace: false,
+            }
+        );
+        assert_eq!(
+            Config::parse(
+                "# comment\nyear = 2019\nsession = \"a#b\\\"c\" # comment\n\ninput_dir = \"/cache\"\nanswers_dir = \"known\"",
+                base_dir
+            )
+            .unwrap(),
+            Config {
+                year: 2019,
+                session: Some("a#b\"c".to_owned()),
+                session_file: PathBuf::from("/aoc/token.txt"),
+                input_dir: PathBuf::from("/cache"),
+                answers_dir: PathBuf::from("/aoc/known"),
+                examples_dir: PathBuf::from("/aoc/examples"),
+                source_dir: PathBuf::from("/aoc/src"),
+                baseline_dir: PathBuf::from("/aoc/baselines"),
+                readme_file: PathBuf::from("/aoc/README.md"),
+                base_url: "https://adventofcode.com".to_owned(),
+                trim_trailing_whitespace: false,
+            }
+        );
+        assert!(Config::parse("year = \"2019\"", base_dir).is_err());
+        assert!(Config::parse("\nyear = true", base_dir)
+            .unwrap_err()
+            .to_string()
+            .ends_with("at line 2 column 8"));
+        assert!(Config::parse("session = abc", base_dir).is_err());
+        assert_eq!(
+            Config::parse("year = 2019\nunknown = 1", base_dir)
+                .unwrap_err()
+                .to_string(),
+            "invalid configuration: aoc.toml: unknown key `unknown`"
+        );
+        assert!(Config::parse("[section]", base_dir).is_err());
+        assert!(
+            Config::parse("trim_trailing_whitespace = true", base_dir)
+                .unwrap()
+                .trim_trailing_whitespace
+        );
+        assert!(Config::parse("trim_trailing_whitespace = 1", base_dir).is_err());
+    }
+
+    #[test]
+    fn year_dirs() {
+        let config = Config::with_base_dir(Path::new("/aoc"));
+        assert_eq!(
+            config.year_dir(&config.input_dir, 2020),
+            PathBuf::from("/aoc/inputs")
+        );
+        assert_eq!(
+            config.year_dir(&config.input_dir, 2019),
+            PathBuf::from("/aoc/inputs/2019")
+        );
+    }
+}
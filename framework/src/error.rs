@@ -6,8 +6,24 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub enum Error {
     #[error("io: {0}")]
     IoError(#[from] std::io::Error),
-    #[error("network error")]
-    NetworkError,
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("invalid configuration: {0}: {1}")]
+    InvalidConfig(&'static str, String),
+    #[error("network error: {0}")]
+    NetworkError(String),
+    #[error("unauthorized, the session cookie is missing or expired")]
+    Unauthorized,
+    #[error("not yet available")]
+    NotYetAvailable,
+    #[error("rate limited by the server")]
+    RateLimited,
+    #[error("server error (HTTP {0})")]
+    ServerError(u16),
+    #[error("unexpected HTTP status {0}")]
+    HttpStatus(u16),
+    #[error("answer rejected: {0}")]
+    AnswerRejected(String),
     #[error("not implemented")]
     NotImplemented,
     #[error("invalid input: {0}")]
@@ -18,6 +34,18 @@ pub enum Error {
     ParseError(crate::parser::AocErrorKind, String),
     #[error("no solution found")]
     NoSolution,
+    #[error("panicked: {0}")]
+    Panic(String),
+    #[error("timed out")]
+    TimedOut,
+    #[error("cancelled")]
+    Cancelled,
+    #[error("{0} part(s) failed")]
+    PartsFailed(usize),
+    #[error("{0} answer(s) differ from the recorded answers")]
+    WrongAnswers(usize),
+    #[error("the implementations of {0} part(s) failed or disagree")]
+    InconsistentVariants(usize),
 }
 
 impl From<!> for Error {
@@ -0,0 +1,445 @@
+use crate::error::Error;
+use fs2::FileExt;
+use std::{
+    fs,
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// The minimum delay between two requests to the server, to avoid putting too
+/// much load on it.
+const DELAY_BETWEEN_REQUESTS: Duration = Duration::from_secs(3);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct RequestThrottle {
+    previous_request_time: Option<Instant>,
+    delay: Duration,
+    /// A file storing the time of the last request, which is shared with other
+    /// processes, so that the delay is honoured across invocations.
+    state_path: Option<PathBuf>,
+}
+
+impl Default for RequestThrottle {
+    fn default() -> Self {
+        RequestThrottle::with_delay(DELAY_BETWEEN_REQUESTS)
+    }
+}
+
+impl RequestThrottle {
+    pub fn with_delay(delay: Duration) -> Self {
+        RequestThrottle {
+            previous_request_time: None,
+            delay,
+            state_path: None,
+        }
+    }
+
+    /// Creates a throttle which stores the time of the last request in the
+    /// given file, and shares it with every process using the same file.
+    pub fn persistent(state_path: PathBuf) -> Self {
+        RequestThrottle {
+            state_path: Some(state_path),
+            ..RequestThrottle::default()
+        }
+    }
+
+    /// Waits until the next request is allowed, and records it as being made.
+    pub fn wait(&mut self) {
+        if let Some(state_path) = &self.state_path {
+            match wait_persistent(state_path, self.delay) {
+                Ok(()) => return,
+                Err(err) => eprintln!(
+                    "warning: cannot use {} to throttle requests: {}",
+                    state_path.display(),
+                    err
+                ),
+            }
+        }
+        if let Some(previous_request_time) = self.previous_request_time {
+            let delta = Instant::now() - previous_request_time;
+            if delta < self.delay {
+                thread::sleep(self.delay - delta);
+            }
+        }
+        self.previous_request_time = Some(Instant::now());
+    }
+}
+
+/// Waits until the delay has passed since the time stored in the file, and
+/// then stores the current time. The file stays locked while waiting, so that
+/// concurrent processes take turns.
+fn wait_persistent(state_path: &Path, delay: Duration) -> io::Result<()> {
+    if let Some(dir_path) = state_path.parent() {
+        fs::create_dir_all(dir_path)?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(state_path)?;
+    file.lock_exclusive()?;
+
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    if let Ok(millis) = contents.trim().parse() {
+        let previous_request_time = UNIX_EPOCH + Duration::from_millis(millis);
+        // A time in the future is ignored, as the clock may have been changed.
+        if let Ok(delta) = SystemTime::now().duration_since(previous_request_time) {
+            if delta < delay {
+                thread::sleep(delay - delta);
+            }
+        }
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    file.set_len(0)?;
+    file.seek(SeekFrom::Start(0))?;
+    write!(file, "{}", now.as_millis())?;
+    file.unlock()
+}
+
+/// How often, and how patiently, to retry requests which failed due to a
+/// transient error.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub attempts: u32,
+    /// The delay before the first retry, which doubles for every next retry.
+    pub initial_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            attempts: 4,
+            initial_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+/// Whether a request that failed with this error could succeed if retried.
+fn is_transient(err: &Error) -> bool {
+    matches!(
+        err,
+        Error::NetworkError(_) | Error::RateLimited | Error::ServerError(_)
+    )
+}
+
+fn status_error(status: u16) -> Error {
+    match status {
+        400 | 401 | 403 => Error::Unauthorized,
+        404 => Error::NotYetAvailable,
+        429 => Error::RateLimited,
+        500..=599 => Error::ServerError(status),
+        _ => Error::HttpStatus(status),
+    }
+}
+
+/// Performs a request, returning the body if the server responded with 200 OK.
+fn execute(request: &mut ureq::Request, form: Option<&str>) -> Result<String, Error> {
+    let request = request.timeout(REQUEST_TIMEOUT);
+    let resp = match form {
+        Some(form) => request
+            .set("content-type", "application/x-www-form-urlencoded")
+            .send_string(form),
+        None => request.call(),
+    };
+    if let Some(err) = resp.synthetic_error() {
+        return Err(Error::NetworkError(err.to_string()));
+    }
+    match resp.status() {
+        200 => resp
+            .into_string()
+            .map_err(|err| Error::NetworkError(err.to_string())),
+        status => Err(status_error(status)),
+    }
+}
+
+/// Performs a request through the throttle, retrying transient failures with
+/// an exponential backoff.
+fn execute_with_retry(
+    throttle: &mut RequestThrottle,
+    retry: &RetryPolicy,
+    mut make_request: impl FnMut() -> ureq::Request,
+    form: Option<&str>,
+) -> Result<String, Error> {
+    let mut backoff = retry.initial_backoff;
+    let mut attempt = 1;
+    loop {
+        throttle.wait();
+        match execute(&mut make_request(), form) {
+            Err(err) if is_transient(&err) && attempt < retry.attempts => {
+                thread::sleep(backoff);
+                backoff *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn session_cookie(session_key: &str) -> String {
+    format!("session={}", session_key)
+}
+
+pub fn get(
+    throttle: &mut RequestThrottle,
+    retry: &RetryPolicy,
+    url: &str,
+    session_key: &str,
+) -> Result<String, Error> {
+    execute_with_retry(
+        throttle,
+        retry,
+        || {
+            let mut request = ureq::get(url);
+            request.set("cookie", &session_cookie(session_key));
+            request
+        },
+        None,
+    )
+}
+
+/// Posts a form, given as `(name, value)` pairs.
+pub fn post_form(
+    throttle: &mut RequestThrottle,
+    retry: &RetryPolicy,
+    url: &str,
+    session_key: &str,
+    form: &[(&str, &str)],
+) -> Result<String, Error> {
+    let form = form
+        .iter()
+        .map(|(name, value)| format!("{}={}", url_encode(name), url_encode(value)))
+        .collect::<Vec<_>>()
+        .join("&");
+    execute_with_retry(
+        throttle,
+        retry,
+        || {
+            let mut request = ureq::post(url);
+            request.set("cookie", &session_cookie(session_key));
+            request
+        },
+        Some(&form),
+    )
+}
+
+fn url_encode(value: &str) -> String {
+    let mut res = String::with_capacity(value.len());
+    for &b in value.as_bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                res.push(b as char)
+            }
+            _ => res.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+pub(crate) mod test_server {
+    use super::RequestThrottle;
+    use crate::{config::Config, input::SessionKey};
+    use std::{
+        env, fs,
+        io::{Read, Write},
+        net::TcpListener,
+        path::PathBuf,
+        process,
+        sync::{Arc, Mutex},
+        thread,
+        time::Duration,
+    };
+
+    /// A minimal HTTP server, which answers every connection with the next one
+    /// of a fixed list of responses, and records the requests it receives.
+    pub struct TestServer {
+        pub url: String,
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl TestServer {
+        pub fn serve(responses: Vec<(u16, &'static str)>) -> TestServer {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let recorded_requests = requests.clone();
+            thread::spawn(move || {
+                for (status, body) in responses {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let request = read_request(&mut stream);
+                    recorded_requests.lock().unwrap().push(request);
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 {} Status\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                }
+            });
+            TestServer { url, requests }
+        }
+
+        pub fn requests(&self) -> Vec<String> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    /// A temporary directory, which is removed when dropped.
+    pub struct TestDir(pub PathBuf);
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Serves the responses, and returns a configuration that downloads from
+    /// the server and stores its files in a temporary directory named after
+    /// the test, along with a throttle that does not wait and a session key.
+    pub fn serve_with_config(
+        name: &str,
+        responses: Vec<(u16, &'static str)>,
+    ) -> (TestServer, TestDir, Config, RequestThrottle, SessionKey) {
+        let dir = TestDir(env::temp_dir().join(format!("aoc-{}-test-{}", name, process::id())));
+        let server = TestServer::serve(responses);
+        let mut config = Config::with_base_dir(&dir.0);
+        config.session = Some("abc".to_owned());
+        config.base_url = server.url.clone();
+        let throttle = RequestThrottle::with_delay(Duration::from_millis(0));
+        (server, dir, config, throttle, SessionKey::default())
+    }
+
+    fn read_request(stream: &mut impl Read) -> String {
+        let mut data = Vec::new();
+        let mut buffer = [0; 1024];
+        loop {
+            let count = stream.read(&mut buffer).unwrap();
+            data.extend_from_slice(&buffer[..count]);
+            let text = String::from_utf8_lossy(&data).into_owned();
+            if let Some(header_end) = text.find("\r\n\r\n") {
+                let content_length = text[..header_end]
+                    .lines()
+                    .filter_map(|line| {
+                        let (name, value) = line.split_at(line.find(':')?);
+                        if name.eq_ignore_ascii_case("content-length") {
+                            value[1..].trim().parse::<usize>().ok()
+                        } else {
+                            None
+                        }
+                    })
+                    .next()
+                    .unwrap_or(0);
+                if count == 0 || data.len() >= header_end + 4 + content_length {
+                    return text;
+                }
+            } else if count == 0 {
+                return text;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{test_server::TestServer, *};
+
+    fn fetch(server: &TestServer, attempts: u32) -> Result<String, Error> {
+        let retry = RetryPolicy {
+            attempts,
+            initial_backoff: Duration::from_millis(1),
+        };
+        get(
+            &mut RequestThrottle::with_delay(Duration::from_millis(0)),
+            &retry,
+            &format!("{}/2020/day/1/input", server.url),
+            "abc",
+        )
+    }
+
+    #[test]
+    fn status_codes() {
+        let server = TestServer::serve(vec![
+            (200, "1\n2\n"),
+            (400, "Puzzle inputs differ by user."),
+            (404, "Please don't repeatedly request this endpoint."),
+            (418, ""),
+        ]);
+        assert_eq!(fetch(&server, 1).unwrap(), "1\n2\n");
+        assert!(matches!(fetch(&server, 1), Err(Error::Unauthorized)));
+        assert!(matches!(fetch(&server, 1), Err(Error::NotYetAvailable)));
+        assert!(matches!(fetch(&server, 1), Err(Error::HttpStatus(418))));
+
+        let requests = server.requests();
+        assert!(requests[0].starts_with("GET /2020/day/1/input HTTP/1.1\r\n"));
+        assert!(requests[0]
+            .to_ascii_lowercase()
+            .contains("cookie: session=abc\r\n"));
+    }
+
+    #[test]
+    fn retries() {
+        let server = TestServer::serve(vec![(500, ""), (429, ""), (200, "ok")]);
+        assert_eq!(fetch(&server, 3).unwrap(), "ok");
+        assert_eq!(server.requests().len(), 3);
+
+        let server = TestServer::serve(vec![(503, ""), (503, "")]);
+        assert!(matches!(fetch(&server, 2), Err(Error::ServerError(503))));
+
+        let server = TestServer::serve(vec![(429, "")]);
+        assert!(matches!(fetch(&server, 1), Err(Error::RateLimited)));
+
+        let server = TestServer::serve(vec![(400, ""), (200, "")]);
+        assert!(matches!(fetch(&server, 3), Err(Error::Unauthorized)));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn persistent_throttle() {
+        let state_path =
+            std::env::temp_dir().join(format!("aoc-throttle-test-{}", std::process::id()));
+        let delay = Duration::from_millis(100);
+        let start = Instant::now();
+        let threads = (0..3)
+            .map(|_| {
+                let state_path = state_path.clone();
+                thread::spawn(move || {
+                    let mut throttle = RequestThrottle::persistent(state_path);
+                    throttle.delay = delay;
+                    throttle.wait();
+                })
+            })
+            .collect::<Vec<_>>();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        // Each throttle only made a single request, but they share the file.
+        // The stored time is truncated to milliseconds, hence the tolerance.
+        assert!(start.elapsed() >= delay * 2 - Duration::from_millis(2));
+
+        let _ = fs::remove_file(state_path);
+    }
+
+    #[test]
+    fn forms() {
+        let server = TestServer::serve(vec![(200, "ok")]);
+        let result = post_form(
+            &mut RequestThrottle::with_delay(Duration::from_millis(0)),
+            &RetryPolicy::default(),
+            &format!("{}/2020/day/1/answer", server.url),
+            "abc",
+            &[("level", "1"), ("answer", "a b&c")],
+        );
+        assert_eq!(result.unwrap(), "ok");
+        let request = &server.requests()[0];
+        assert!(request.starts_with("POST /2020/day/1/answer HTTP/1.1\r\n"));
+        assert!(request.ends_with("\r\n\r\nlevel=1&answer=a%20b%26c"));
+    }
+}
@@ -0,0 +1,354 @@
+use crate::{
+    config::Config,
+    error::Error,
+    http::{self, RequestThrottle, RetryPolicy},
+    options::Options,
+    unlock,
+};
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+/// Loads the input of a day, either from the `--input` path, or from the input
+/// cache of the profile, or by downloading it. With `--wait`, puzzles that have
+/// not unlocked yet are downloaded as soon as they do. The input is normalized,
+/// and a single trailing line break is removed.
+pub fn load_input(
+    config: &Config,
+    throttle: &mut RequestThrottle,
+    session_key: &mut SessionKey,
+    options: &Options,
+    year: u32,
+    day_nr: u32,
+    profile: Option<&str>,
+) -> Result<String, Error> {
+    let mut input = match &options.input {
+        Some(path) => read_input_file(config, path)?,
+        None => get_day_input(
+            config,
+            throttle,
+            session_key,
+            options,
+            profile,
+            year,
+            day_nr,
+        )?,
+    };
+    if input.ends_with('\n') {
+        input.pop();
+    }
+    Ok(input)
+}
+
+pub(crate) fn get_session_key<'s>(
+    config: &Config,
+    session_key: &'s mut SessionKey,
+) -> io::Result<&'s str> {
+    if session_key.0.is_none() {
+        let key = match &config.session {
+            Some(key) => key.clone(),
+            None => fs::read_to_string(&config.session_file)?.trim().to_string(),
+        };
+        session_key.0 = Some(key);
+    }
+    Ok(session_key.0.as_deref().unwrap())
+}
+
+/// Returns where the input of a day is stored. Inputs of the default profile
+/// are stored directly in the input directory of the year, others in a
+/// subdirectory of it.
+pub(crate) fn get_day_input_path(
+    config: &Config,
+    profile: Option<&str>,
+    year: u32,
+    day_nr: u32,
+) -> PathBuf {
+    let mut path = config.year_dir(&config.input_dir, year);
+    if let Some(profile) = profile {
+        path.push(profile);
+    }
+    path.push(format!("day{:0>2}.txt", day_nr));
+    path
+}
+
+pub fn has_day_input(config: &Config, profile: Option<&str>, year: u32, day_nr: u32) -> bool {
+    get_day_input_path(config, profile, year, day_nr).is_file()
+}
+
+/// Lists the names of all profiles of a year, which are the subdirectories of
+/// its input directory, except for those holding the inputs of other years.
+pub fn list_profiles(config: &Config, year: u32) -> Result<Vec<String>, Error> {
+    let entries = match fs::read_dir(config.year_dir(&config.input_dir, year)) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    let mut profiles = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            if let Some(name) = entry.file_name().to_str() {
+                if name.parse::<u32>().is_err() {
+                    profiles.push(name.to_owned());
+                }
+            }
+        }
+    }
+    profiles.sort();
+    Ok(profiles)
+}
+
+#[derive(Default)]
+pub struct SessionKey(Option<String>);
+
+/// Normalizes an input, by removing a byte order mark, and converting CRLF line
+/// breaks to LF. If `trim` is set, trailing whitespace is also removed from
+/// every line, and from the end of the input.
+pub fn normalize_input(input: &str, trim: bool) -> String {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut res = input.replace("\r\n", "\n");
+    if trim {
+        res = res
+            .lines()
+            .map(|line| line.trim_end_matches(&[' ', '\t'][..]))
+            .collect::<Vec<_>>()
+            .join("\n");
+        res.truncate(res.trim_end().len());
+    }
+    res
+}
+
+/// Checks that an input only consists of printable ASCII characters and line
+/// breaks, and otherwise describes the first character that is not.
+fn validate_input(input: &str) -> Result<(), String> {
+    for (line_nr, line) in input.split('\n').enumerate() {
+        if let Some((column, c)) = line
+            .char_indices()
+            .find(|&(_, c)| !c.is_ascii() || c.is_ascii_control())
+        {
+            return Err(format!(
+                "invalid character {:?} (U+{:04X}) at line {}, column {}",
+                c,
+                c as u32,
+                line_nr + 1,
+                column + 1
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Decodes, normalizes and validates the contents of an input, where `origin`
+/// describes where it came from for use in errors.
+fn decode_input(contents: Vec<u8>, config: &Config, origin: &str) -> Result<String, Error> {
+    let invalid = |reason: String| Error::InvalidInputDyn(format!("{}: {}", origin, reason));
+    let input = String::from_utf8(contents).map_err(|err| {
+        let valid = &err.as_bytes()[..err.utf8_error().valid_up_to()];
+        let line_start = valid.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+        invalid(format!(
+            "invalid UTF-8 byte 0x{:02x} at line {}, column {}",
+            err.as_bytes()[valid.len()],
+            valid.split(|&b| b == b'\n').count(),
+            valid.len() - line_start + 1
+        ))
+    })?;
+    let input = normalize_input(&input, config.trim_trailing_whitespace);
+    validate_input(&input).map_err(invalid)?;
+    Ok(input)
+}
+
+/// Computes the fingerprint of an input, which is its 64-bit FNV-1a hash. It
+/// only serves to detect accidental changes.
+fn fingerprint(contents: &[u8]) -> String {
+    let hash = contents.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Returns where the fingerprint of a downloaded input is stored.
+fn get_fingerprint_path(input_path: &Path) -> PathBuf {
+    input_path.with_extension("fingerprint")
+}
+
+/// Reads the input from an explicitly specified file, or from stdin if the
+/// path is `-`. The input cache and downloader are bypassed.
+pub(crate) fn read_input_file(config: &Config, path: &Path) -> Result<String, Error> {
+    let mut contents = Vec::new();
+    if path == Path::new("-") {
+        io::stdin().read_to_end(&mut contents)?;
+        decode_input(contents, config, "stdin")
+    } else {
+        contents = fs::read(path)?;
+        decode_input(contents, config, &path.display().to_string())
+    }
+}
+
+fn get_day_input(
+    config: &Config,
+    throttle: &mut RequestThrottle,
+    session_key: &mut SessionKey,
+    options: &Options,
+    profile: Option<&str>,
+    year: u32,
+    day_nr: u32,
+) -> Result<String, Error> {
+    let file_path = get_day_input_path(config, profile, year, day_nr);
+    let fingerprint_path = get_fingerprint_path(&file_path);
+    match fs::read(&file_path) {
+        Ok(contents) => {
+            // Only downloaded inputs have a fingerprint to compare against.
+            let intact = match fs::read_to_string(&fingerprint_path) {
+                Ok(expected) => expected.trim() == fingerprint(&contents),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => true,
+                Err(err) => return Err(err.into()),
+            };
+            if intact || !options.refresh || profile.is_some() {
+                // Inputs are edited on purpose while watching them.
+                if !intact && !options.watch {
+                    // Inputs of profiles are never downloaded, so refreshing
+                    // them is not an option.
+                    let hint = match profile {
+                        Some(_) => "",
+                        None => ", use --refresh to download it again",
+                    };
+                    eprintln!(
+                        "warning: {} was changed since it was downloaded{}",
+                        file_path.display(),
+                        hint
+                    );
+                }
+                return decode_input(contents, config, &file_path.display().to_string());
+            }
+        }
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err.into()),
+    }
+
+    // The session key belongs to the default profile, so other profiles must
+    // provide their inputs manually.
+    if profile.is_some() {
+        return Err(Error::InvalidInputDyn(format!(
+            "no input at {}",
+            file_path.display()
+        )));
+    }
+
+    let session_key = get_session_key(config, session_key)?;
+    if options.wait {
+        unlock::wait_for_unlock(year, day_nr);
+    }
+    let url = format!("{}/{}/day/{}/input", config.base_url, year, day_nr);
+    // Only successful responses make it past here, so error pages, such as
+    // for puzzles that are not unlocked yet, are never cached as inputs.
+    let contents = http::get(throttle, &RetryPolicy::default(), &url, session_key)?;
+    let input = decode_input(contents.clone().into_bytes(), config, &url)?;
+
+    let mut dir_path = file_path.clone();
+    dir_path.pop();
+    let _ = fs::create_dir_all(dir_path);
+    if fs::write(file_path, &contents).is_ok() {
+        let _ = fs::write(fingerprint_path, fingerprint(contents.as_bytes()));
+    }
+
+    Ok(input)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::http::test_server::serve_with_config;
+
+    #[test]
+    fn normalization() {
+        assert_eq!(
+            normalize_input("\u{feff}a\r\nb \r\n\r\n", false),
+            "a\nb \n\n"
+        );
+        assert_eq!(normalize_input("a\t\n b  \n\n", true), "a\n b");
+        assert_eq!(validate_input("a b\n#.#\n"), Ok(()));
+        assert_eq!(
+            validate_input("ab\nc\rd"),
+            Err("invalid character '\\r' (U+000D) at line 2, column 2".to_owned())
+        );
+        assert_eq!(
+            validate_input("\n\nabé"),
+            Err("invalid character 'é' (U+00E9) at line 3, column 3".to_owned())
+        );
+
+        let config = Config::with_base_dir(Path::new(""));
+        let err = decode_input(b"a\r\nb\xff".to_vec(), &config, "day01.txt").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input: day01.txt: invalid UTF-8 byte 0xff at line 2, column 2"
+        );
+        assert_eq!(
+            decode_input(b"a\r\nb\r\n".to_vec(), &config, "").unwrap(),
+            "a\nb\n"
+        );
+    }
+
+    #[test]
+    fn only_successful_responses_are_cached() {
+        let (server, _dir, config, mut throttle, mut session_key) = serve_with_config(
+            "input",
+            vec![
+                (
+                    404,
+                    "Please don't repeatedly request this endpoint before it unlocks!",
+                ),
+                (200, "1\n2\n3\n"),
+            ],
+        );
+        let (throttle, session_key) = (&mut throttle, &mut session_key);
+        let options = &Options::default();
+
+        let result = get_day_input(&config, throttle, session_key, options, None, 2020, 1);
+        assert!(matches!(result, Err(Error::NotYetAvailable)));
+        assert!(!get_day_input_path(&config, None, 2020, 1).exists());
+
+        let result = get_day_input(&config, throttle, session_key, options, None, 2020, 1);
+        assert_eq!(result.unwrap(), "1\n2\n3\n");
+        assert_eq!(
+            fs::read_to_string(get_day_input_path(&config, None, 2020, 1)).unwrap(),
+            "1\n2\n3\n"
+        );
+        // Cached inputs are used without contacting the server again.
+        assert_eq!(
+            get_day_input(&config, throttle, session_key, options, None, 2020, 1).unwrap(),
+            "1\n2\n3\n"
+        );
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[test]
+    fn fingerprints() {
+        let (server, _dir, config, mut throttle, mut session_key) =
+            serve_with_config("fingerprint", vec![(200, "1\n2\n3\n"), (200, "1\n2\n3\n")]);
+        let (throttle, session_key) = (&mut throttle, &mut session_key);
+        let mut options = Options::default();
+        let input_path = get_day_input_path(&config, None, 2020, 1);
+
+        get_day_input(&config, throttle, session_key, &options, None, 2020, 1).unwrap();
+        assert_eq!(
+            fs::read_to_string(get_fingerprint_path(&input_path)).unwrap(),
+            fingerprint(b"1\n2\n3\n")
+        );
+
+        // A changed input is still used, unless it is refreshed.
+        fs::write(&input_path, "1\n2\n").unwrap();
+        assert_eq!(
+            get_day_input(&config, throttle, session_key, &options, None, 2020, 1).unwrap(),
+            "1\n2\n"
+        );
+        options.refresh = true;
+        assert_eq!(
+            get_day_input(&config, throttle, session_key, &options, None, 2020, 1).unwrap(),
+            "1\n2\n3\n"
+        );
+        assert_eq!(fs::read_to_string(&input_path).unwrap(), "1\n2\n3\n");
+        assert_eq!(server.requests().len(), 2);
+    }
+}
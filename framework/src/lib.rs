@@ -7,184 +7,322 @@
     const_ptr_offset,
     const_raw_ptr_deref,
     negative_impls,
-    never_type
+    never_type,
+    thread_local
 )]
 
 pub use paste::paste;
 
+pub mod answers;
+pub mod baseline;
+pub mod bench;
 pub mod bootstrap;
+pub mod cancel;
+pub mod config;
 pub mod error;
+pub mod http;
+pub mod input;
 pub mod iter;
+pub mod memory;
 pub mod num;
+pub mod options;
+pub mod panic;
+pub mod parallel;
 pub mod parser;
+pub mod profiles;
+pub mod puzzle;
+pub mod report;
+pub mod scaffold;
+pub mod submit;
 pub mod test;
+pub mod timeout;
 pub mod traits;
+pub mod unlock;
+pub mod variants;
+pub mod watch;
 
 use arrayvec::ArrayVec;
-use std::{
-    fs,
-    io::{self, Read},
-    path::PathBuf,
-    thread,
-    time::{Duration, Instant},
-};
-use traits::ResultWhereValueIsErrorExt;
-
-pub fn run(days: &[(&'static str, &'static dyn traits::Day)]) -> Result<(), error::Error> {
-    use colored::Colorize;
-    println!(
-        "{} {} {} {}",
-        "Advent".bright_red().bold(),
-        "of".bright_white(),
-        "Code".bright_green().bold(),
-        "2020".bright_blue()
-    );
-
-    let session_key = &mut SessionKey::default();
-    let throttle = &mut RequestThrottle::default();
-
-    let args = std::env::args_os().skip(1).collect::<Vec<_>>();
-    let args = args.iter().filter_map(|x| x.to_str()).collect::<Vec<_>>();
-    let is_module_included = |name| args.len() == 0 || args.contains(name);
-
-    for (module_name, day) in days {
-        if !is_module_included(module_name) {
-            continue;
-        }
-
-        let day_nr = day.nr();
-        let mut input = get_day_input(throttle, session_key, day_nr)?;
+use std::sync::Arc;
 
-        if input.chars().last() == Some('\n') {
-            input.pop();
-        }
+/// A day as registered by `main!`: its year, where `None` means the default
+/// year from the configuration, its module name, and the day itself.
+pub type DayEntry = (Option<u32>, &'static str, &'static dyn traits::Day);
 
-        let start_time = std::time::Instant::now();
-        let results = day.evaluate(input);
-        let duration = std::time::Instant::now() - start_time;
+pub fn run(days: &[DayEntry]) -> Result<(), error::Error> {
+    let options = options::Options::from_env()?;
+    let config = config::Config::load()?;
+    panic::install_hook();
+    let session_key = &mut input::SessionKey::default();
+    let throttle = &mut http::RequestThrottle::persistent(config.input_dir.join(".last_request"));
+    let days = days
+        .iter()
+        .map(|&(year, module_name, day)| (year.unwrap_or(config.year), module_name, day))
+        .collect::<Vec<_>>();
+    // Checked along with the options, before anything is printed.
+    let selected_days = days
+        .iter()
+        .filter(|(year, module_name, _)| options.is_day_included(*year, module_name, config.year))
+        .map(|&(year, _, day)| (year, day))
+        .collect::<Vec<_>>();
+    if options.input.is_some() && selected_days.len() != 1 {
+        return Err(error::Error::InvalidArgument(
+            "--input requires exactly one day to be selected".to_owned(),
+        ));
+    }
 
-        const VALUE_ALIGNMENT: usize = 16;
+    match &options.command {
+        options::Command::Run => {}
+        options::Command::Submit { day, part } => {
+            let (year, day) = find_day(&days, day, config.year)?;
+            return submit::run(&config, throttle, session_key, &options, year, day, *part);
+        }
+        options::Command::Examples { day } => {
+            let (year, day) = find_day(&days, day, config.year)?;
+            let (examples, highlights) =
+                puzzle::update_examples(&config, throttle, session_key, year, day.nr())?;
+            println!(
+                "extracted {} example(s) and {} highlight(s) to {}",
+                examples,
+                highlights,
+                puzzle::get_puzzle_dir(&config, year, day.nr()).display()
+            );
+            return Ok(());
+        }
+        options::Command::New { day } => {
+            let module_path = scaffold::new_day(&config, day)?;
+            println!(
+                "created {} and registered it in main.rs",
+                module_path.display()
+            );
+            return Ok(());
+        }
+    }
 
-        let results: ArrayVec<[_; 2]> = results
-            .into_iter()
-            .map(|(pt_name, result)| (pt_name, result.map_err(|err| err.to_string())))
-            .collect();
-        let use_expanded_format = results
-            .iter()
-            .any(|(_, result)| result.unwrap_either().contains('\n'));
+    // Loaded up front, so that a missing baseline is reported before running.
+    let compared_baseline = match &options.compare {
+        Some(name) => Some(baseline::Baseline::load(&config, name)?),
+        None => None,
+    };
 
-        let duration_nanos = duration.as_nanos();
-        let duration_ms = duration_nanos / 1_000_000;
-        let duration_decimals = duration_nanos / 1_000 % 1_000 / 10;
+    let mut reporter = report::reporter(&options, &config);
+    reporter.begin();
 
-        print!(
-            "{} ({} ms)",
-            format!("day{:0>2}", day_nr).bright_blue(),
-            format!("{: >3}.{:0>2}", duration_ms, duration_decimals).bright_white(),
-        );
-        if use_expanded_format {
-            println!();
+    // Every selected day is evaluated once for each profile it applies to.
+    let mut runs = Vec::new();
+    for &(year, day) in &selected_days {
+        let profiles = if options.all_profiles {
+            std::iter::once(None)
+                .chain(input::list_profiles(&config, year)?.into_iter().map(Some))
+                .collect()
         } else {
-            print!(" |");
-        }
-
-        for (pt_name, result) in results {
-            let pt_ident = pt_name.bright_green();
-            let value = match result {
-                Ok(value) => value.bright_white().bold(),
-                Err(err) => err.bright_red().bold().underline(),
-            };
-            if use_expanded_format {
-                println!("{}\n{}", pt_ident, value);
-            } else {
-                print!(" {} {:>width$} |", pt_ident, value, width = VALUE_ALIGNMENT);
+            vec![options.profile.clone()]
+        };
+        for profile in profiles {
+            if !options.all_profiles
+                || profile.is_none()
+                || input::has_day_input(&config, profile.as_deref(), year, day.nr())
+            {
+                runs.push((year, day, profile));
             }
         }
-        if !use_expanded_format {
-            println!();
-        }
     }
 
-    Ok(())
-}
+    if options.watch {
+        return watch::run(
+            &config,
+            throttle,
+            session_key,
+            &options,
+            &mut *reporter,
+            &runs,
+        );
+    }
 
-fn get_session_key(session_key: &mut SessionKey) -> io::Result<&str> {
-    if session_key.0.is_none() {
-        let key = fs::read_to_string("token.txt")?.trim().to_string();
-        session_key.0 = Some(key);
+    if options.variants {
+        return variants::run(&config, throttle, session_key, &options, &runs);
     }
-    Ok(session_key.0.as_deref().unwrap())
-}
 
-fn get_day_input_path(day_nr: u32) -> PathBuf {
-    let mut path = PathBuf::new();
-    path.push("inputs");
-    path.push(format!("day{:0>2}.txt", day_nr));
-    path
-}
+    let mut summary = report::Summary::default();
+    // Only the text output compares the inputs of every profile side by side.
+    let mut comparison = if options.all_profiles && options.format == report::Format::Text {
+        Some(profiles::Comparison::default())
+    } else {
+        None
+    };
+    let mut timings = baseline::Baseline::default();
+    {
+        let mut handle_evaluation = |year, day_nr, profile: Option<String>, evaluation, stats| {
+            let verdicts = check_answers(
+                &config,
+                &options,
+                year,
+                day_nr,
+                profile.as_deref(),
+                &evaluation,
+            )?;
+            if options.has_baseline() {
+                timings.add(year, day_nr, profile.as_deref(), &evaluation);
+            }
 
-const DELAY_BETWEEN_REQUESTS: Duration = Duration::from_secs(3);
+            let day_report = report::DayReport {
+                year,
+                day_nr,
+                profile,
+                evaluation,
+                stats,
+                verdicts,
+            };
+            summary.add(&day_report);
+            if let Some(comparison) = &mut comparison {
+                comparison.add(&day_report);
+            }
+            reporter.report_day(&day_report);
+            Ok(())
+        };
 
-#[derive(Default)]
-struct SessionKey(Option<String>);
+        if options.jobs > 1 {
+            let shared_options = Arc::new(options.clone());
+            let mut tasks = Vec::new();
+            for (year, day, profile) in runs.iter().cloned() {
+                let input = input::load_input(
+                    &config,
+                    throttle,
+                    session_key,
+                    &options,
+                    year,
+                    day.nr(),
+                    profile.as_deref(),
+                )?;
+                let options = shared_options.clone();
+                tasks.push(Box::new(move || {
+                    // Isolated days are timed again afterwards, which needs the
+                    // input.
+                    let retained_input = if options.isolated {
+                        Some(input.clone())
+                    } else {
+                        None
+                    };
+                    let (evaluation, stats) = evaluate_day(day, input, &options);
+                    (year, day, profile, retained_input, evaluation, stats)
+                }) as parallel::Task<_>);
+            }
+            let mut isolated_runs: Vec<(u32, &dyn traits::Day, _, String, _)> = Vec::new();
+            parallel::run_in_order(
+                options.jobs,
+                tasks,
+                |(year, day, profile, retained_input, evaluation, stats)| match retained_input {
+                    Some(input) => {
+                        isolated_runs.push((year, day, profile, input, evaluation));
+                        Ok(())
+                    }
+                    None => handle_evaluation(year, day.nr(), profile, evaluation, stats),
+                },
+            )?;
+            // The answers are known by now, and every day is timed again on its
+            // own, so that the days do not slow each other down.
+            for (year, day, profile, input, mut evaluation) in isolated_runs {
+                let (timed_evaluation, stats) = evaluate_day(day, input, &options);
+                evaluation.take_timings(&timed_evaluation);
+                handle_evaluation(year, day.nr(), profile, evaluation, stats)?;
+            }
+        } else {
+            for (year, day, profile) in runs.iter().cloned() {
+                let input = input::load_input(
+                    &config,
+                    throttle,
+                    session_key,
+                    &options,
+                    year,
+                    day.nr(),
+                    profile.as_deref(),
+                )?;
+                let (evaluation, stats) = evaluate_day(day, input, &options);
+                handle_evaluation(year, day.nr(), profile, evaluation, stats)?;
+            }
+        }
+    }
 
-#[derive(Default)]
-struct RequestThrottle(Option<Instant>);
+    if let Some(comparison) = &comparison {
+        comparison.print(config.year);
+    }
+    reporter.finish(&summary)?;
+    if let (Some(name), Some(compared_baseline)) = (&options.compare, &compared_baseline) {
+        let missing = timings.missing(compared_baseline, |key| {
+            runs.iter().any(|(year, day, profile)| {
+                (*year, day.nr(), profile.as_deref())
+                    == (key.year, key.day_nr, key.profile.as_deref())
+            })
+        });
+        baseline::print_comparison(
+            name,
+            &timings.compare(compared_baseline),
+            &missing,
+            options.threshold.unwrap_or(baseline::DEFAULT_THRESHOLD),
+            config.year,
+        );
+    }
+    if let Some(name) = &options.save_baseline {
+        timings.save(&config, name)?;
+    }
+    if summary.failed > 0 {
+        return Err(error::Error::PartsFailed(summary.failed));
+    }
+    if summary.wrong > 0 && !options.record {
+        return Err(error::Error::WrongAnswers(summary.wrong));
+    }
+    Ok(())
+}
 
-fn is_valid_input(input: &str) -> bool {
-    input
-        .chars()
-        .all(|c| c.is_ascii() && !c.is_ascii_control() || c == '\n')
+/// Finds a day by a selector such as `day07` or `2019:day05`.
+fn find_day(
+    days: &[(u32, &'static str, &'static dyn traits::Day)],
+    selector: &str,
+    default_year: u32,
+) -> Result<(u32, &'static dyn traits::Day), error::Error> {
+    let selected = options::parse_day_selector(selector, default_year);
+    days.iter()
+        .find(|&&(year, module_name, _)| selected == Some((year, module_name)))
+        .map(|&(year, _, day)| (year, day))
+        .ok_or_else(|| error::Error::InvalidArgument(format!("unknown day {}", selector)))
 }
 
-fn get_day_input(
-    throttle: &mut RequestThrottle,
-    session_key: &mut SessionKey,
+/// Compares the answers of an evaluation against the recorded answers, and
+/// stores them instead when recording.
+fn check_answers(
+    config: &config::Config,
+    options: &options::Options,
+    year: u32,
     day_nr: u32,
-) -> Result<String, error::Error> {
-    let file_path = get_day_input_path(day_nr);
-    if let Some(contents) = fs::read(&file_path)
-        .ok()
-        .and_then(|input| String::from_utf8(input).ok())
-        .filter(|input| is_valid_input(&input))
-    {
-        return Ok(contents);
+    profile: Option<&str>,
+    evaluation: &traits::Evaluation,
+) -> Result<ArrayVec<[Option<answers::Verdict>; 2]>, error::Error> {
+    // Recorded answers only apply to the inputs of profiles.
+    if options.input.is_some() {
+        return Ok(ArrayVec::new());
     }
-
-    let now = Instant::now();
-    if let Some(previous_request_time) = throttle.0 {
-        let delta = now - previous_request_time;
-        if delta < DELAY_BETWEEN_REQUESTS {
-            thread::sleep(DELAY_BETWEEN_REQUESTS - delta);
-        }
+    let mut answers = answers::Answers::load(config, profile, year, day_nr)?;
+    let verdicts = answers.verdicts(evaluation);
+    if options.record {
+        answers.record(evaluation);
+        answers.save(config, profile, year, day_nr)?;
     }
+    Ok(verdicts)
+}
 
-    let session_key = get_session_key(session_key)?;
-    throttle.0 = Some(now);
-
-    let resp = ureq::get(&format!(
-        "https://adventofcode.com/2020/day/{}/input",
-        day_nr
-    ))
-    .set("cookie", &format!("session={}", session_key))
-    .timeout(Duration::from_secs(5))
-    .call();
-
-    if let Some(_err) = resp.synthetic_error() {
-        return Err(error::Error::NetworkError);
+/// Evaluates a day in the way requested by the options.
+fn evaluate_day(
+    day: &'static dyn traits::Day,
+    input: String,
+    options: &options::Options,
+) -> (traits::Evaluation, Option<bench::Statistics>) {
+    match options.bench {
+        Some(runs) => {
+            let (evaluation, stats) = bench::run(day, &input, runs);
+            (evaluation, Some(stats))
+        }
+        None => match options.timeout {
+            Some(limit) => (timeout::evaluate(day, input, limit), None),
+            None => (day.evaluate(input), None),
+        },
     }
-
-    let mut contents = Vec::new();
-    resp.into_reader().read_to_end(&mut contents)?;
-    let contents = String::from_utf8(contents)
-        .ok()
-        .filter(|input| is_valid_input(&input))
-        .ok_or(error::Error::InvalidInput("invalid characters in input"))?;
-
-    let mut dir_path = file_path.clone();
-    dir_path.pop();
-    let _ = fs::create_dir_all(dir_path);
-    let _ = fs::write(file_path, &contents);
-
-    Ok(contents)
 }
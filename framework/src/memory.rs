@@ -0,0 +1,179 @@
+//! Counting of the allocations made by every phase of a day.
+//!
+//! Counting is opt-in, by building with the `count-allocations` feature, which
+//! makes `main!` install [`CountingAllocator`] as the global allocator. Only
+//! allocations made on the thread evaluating a phase are counted, and the
+//! counting itself slightly increases the measured durations.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+/// Whether allocations are counted.
+pub const ENABLED: bool = cfg!(feature = "count-allocations");
+
+/// The allocations made while running a function.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Allocations {
+    /// The number of allocations, including reallocations.
+    pub count: usize,
+    /// The total size of all allocations.
+    pub bytes: usize,
+    /// The largest number of bytes that were allocated at the same time, on
+    /// top of what was already allocated when the function started.
+    pub peak: usize,
+}
+
+#[thread_local]
+static COUNT: Cell<usize> = Cell::new(0);
+#[thread_local]
+static BYTES: Cell<usize> = Cell::new(0);
+/// Memory may be freed on another thread than it was allocated on, so the
+/// number of live bytes on a thread can become negative.
+#[thread_local]
+static LIVE: Cell<isize> = Cell::new(0);
+#[thread_local]
+static PEAK: Cell<isize> = Cell::new(0);
+
+fn record_allocation(size: usize) {
+    COUNT.set(COUNT.get() + 1);
+    BYTES.set(BYTES.get().wrapping_add(size));
+    let live = LIVE.get().wrapping_add(size as isize);
+    LIVE.set(live);
+    if live > PEAK.get() {
+        PEAK.set(live);
+    }
+}
+
+fn record_deallocation(size: usize) {
+    LIVE.set(LIVE.get().wrapping_sub(size as isize));
+}
+
+/// Wraps the system allocator, counting the allocations of every thread.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_allocation(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_allocation(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record_deallocation(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record_deallocation(layout.size());
+            record_allocation(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Installs [`CountingAllocator`] as the global allocator when counting is
+/// enabled. Used by `main!`.
+#[cfg(feature = "count-allocations")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! global_allocator {
+    () => {
+        #[global_allocator]
+        static ALLOCATOR: $crate::memory::CountingAllocator = $crate::memory::CountingAllocator;
+    };
+}
+
+#[cfg(not(feature = "count-allocations"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! global_allocator {
+    () => {};
+}
+
+/// Runs a function, and counts the allocations it makes on the current thread.
+/// Returns `None` for the allocations if counting is not enabled.
+pub fn measure<T, F: FnOnce() -> T>(f: F) -> (T, Option<Allocations>) {
+    if !ENABLED {
+        return (f(), None);
+    }
+    let (count, bytes, live) = (COUNT.get(), BYTES.get(), LIVE.get());
+    let outer_peak = PEAK.replace(live);
+    let value = f();
+    let allocations = Allocations {
+        count: COUNT.get() - count,
+        bytes: BYTES.get().wrapping_sub(bytes),
+        peak: (PEAK.get() - live) as usize,
+    };
+    PEAK.set(PEAK.get().max(outer_peak));
+    (value, Some(allocations))
+}
+
+/// Formats a number of bytes using binary prefixes, such as `1.5 MiB`.
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+impl Allocations {
+    /// Describes the allocations, such as `12 allocs, 1.5 MiB, peak 1.0 MiB`.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} allocs, {}, peak {}",
+            self.count,
+            format_bytes(self.bytes),
+            format_bytes(self.peak)
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(30 * 1024 * 1024), "30.0 MiB");
+    }
+
+    #[test]
+    fn counting() {
+        let (_, allocations) = measure(|| {
+            record_allocation(100);
+            record_allocation(50);
+            record_deallocation(100);
+            record_allocation(20);
+        });
+        assert_eq!(allocations.is_some(), ENABLED);
+        if let Some(allocations) = allocations {
+            assert_eq!(allocations.count, 3);
+            assert_eq!(allocations.bytes, 170);
+            assert_eq!(allocations.peak, 150);
+        }
+    }
+}
@@ -0,0 +1,638 @@
+use crate::{error::Error, report::Format};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+/// What the runner is asked to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Evaluate the selected days.
+    Run,
+    /// Submit the answer to one part of a day, such as `submit day07 pt1`. The
+    /// day is a selector, which may include the year.
+    Submit { day: String, part: usize },
+    /// Download the puzzle description of a day, and extract its examples,
+    /// such as `examples day07`.
+    Examples { day: String },
+    /// Create the module of a new day from a template, such as `new day07`.
+    New { day: String },
+}
+
+impl Default for Command {
+    fn default() -> Self {
+        Command::Run
+    }
+}
+
+/// Command-line options accepted by the runner.
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    pub command: Command,
+    /// The days to run, such as `day07` for a day of the default year, or
+    /// `2019:day05` for another year. Empty means all days.
+    pub days: Vec<String>,
+    /// When set, each day is evaluated this many times and timing statistics
+    /// are reported instead of a single duration.
+    pub bench: Option<usize>,
+    /// The format in which results are printed.
+    pub format: Format,
+    /// Whether to store the computed answers as the known-good answers,
+    /// rather than checking against them.
+    pub record: bool,
+    /// The maximum duration of each phase, after which it is reported as timed
+    /// out.
+    pub timeout: Option<Duration>,
+    /// The number of days to evaluate concurrently.
+    pub jobs: usize,
+    /// Whether to time every day again on its own after evaluating the days
+    /// concurrently, so that their timings do not affect each other.
+    pub isolated: bool,
+    /// A file to read the input from instead of the input cache, where `-`
+    /// means stdin. Only allowed when running a single day.
+    pub input: Option<PathBuf>,
+    /// The profile whose inputs are used, stored in `inputs/<profile>`.
+    pub profile: Option<String>,
+    /// Whether to run every day against the inputs of every profile.
+    pub all_profiles: bool,
+    /// Whether to wait for puzzles that have not unlocked yet, and download
+    /// their inputs the moment they do.
+    pub wait: bool,
+    /// Whether to download cached inputs again when they no longer match the
+    /// fingerprint taken when they were downloaded.
+    pub refresh: bool,
+    /// Whether to keep evaluating the selected days whenever their inputs or
+    /// examples change. Edited inputs are not reported as changed since they
+    /// were downloaded.
+    pub watch: bool,
+    /// The name under which to store the timings of this run as a baseline.
+    pub save_baseline: Option<String>,
+    /// The name of a baseline to compare the timings of this run against.
+    pub compare: Option<String>,
+    /// The change in percent beyond which a phase is highlighted when
+    /// comparing against a baseline.
+    pub threshold: Option<f64>,
+    /// Whether to leave the answers out of the Markdown table.
+    pub redact: bool,
+    /// Whether to write the Markdown table into the marked region of the
+    /// README, rather than printing it.
+    pub update_readme: bool,
+    /// Whether to run every implementation of the parts of days that have
+    /// alternatives, and check that they agree.
+    pub variants: bool,
+}
+
+impl Options {
+    pub fn from_env() -> Result<Options, Error> {
+        let args = std::env::args_os().skip(1).collect::<Vec<_>>();
+        let args = args.iter().filter_map(|x| x.to_str()).collect::<Vec<_>>();
+        Options::parse(&args)
+    }
+
+    pub fn parse(args: &[&str]) -> Result<Options, Error> {
+        let mut options = Options {
+            jobs: 1,
+            ..Options::default()
+        };
+        let mut args = args.iter().cloned().peekable();
+        match args.peek() {
+            Some(&"submit") => {
+                args.next();
+                let invalid = || Error::InvalidArgument("usage: submit dayXX pt1|pt2".to_owned());
+                let day = parse_day(&mut args).ok_or_else(invalid)?;
+                let part = match args.next() {
+                    Some("pt1") => 1,
+                    Some("pt2") => 2,
+                    _ => return Err(invalid()),
+                };
+                options.command = Command::Submit { day, part };
+            }
+            Some(&"examples") => {
+                args.next();
+                let day = parse_day(&mut args)
+                    .ok_or_else(|| Error::InvalidArgument("usage: examples dayXX".to_owned()))?;
+                options.command = Command::Examples { day };
+            }
+            Some(&"new") => {
+                args.next();
+                let day = parse_day(&mut args)
+                    .ok_or_else(|| Error::InvalidArgument("usage: new dayXX".to_owned()))?;
+                options.command = Command::New { day };
+            }
+            _ => {}
+        }
+        while let Some(arg) = args.next() {
+            match arg {
+                "--bench" => options.bench = Some(parse_count(arg, args.next())?),
+                "--format" => options.format = parse_value(arg, args.next())?,
+                "--record" => options.record = true,
+                "--timeout" => options.timeout = Some(parse_seconds(arg, args.next())?),
+                "--jobs" => options.jobs = parse_count(arg, args.next())?,
+                "--isolated" => options.isolated = true,
+                "--input" => {
+                    let path = args.next().ok_or_else(|| {
+                        Error::InvalidArgument("--input expects a path".to_owned())
+                    })?;
+                    options.input = Some(PathBuf::from(path));
+                }
+                "--profile" => options.profile = Some(parse_profile(arg, args.next())?),
+                "--all-profiles" => options.all_profiles = true,
+                "--wait" => options.wait = true,
+                "--refresh" => options.refresh = true,
+                "--watch" => options.watch = true,
+                "--save-baseline" => options.save_baseline = Some(parse_name(arg, args.next())?),
+                "--compare" => options.compare = Some(parse_name(arg, args.next())?),
+                "--variants" => options.variants = true,
+                "--markdown" => options.format = Format::Markdown,
+                "--redact" => options.redact = true,
+                "--update-readme" => options.update_readme = true,
+                "--threshold" => {
+                    let threshold = args
+                        .next()
+                        .and_then(|value| value.trim_end_matches('%').parse::<f64>().ok())
+                        .filter(|&threshold| threshold >= 0.0 && threshold.is_finite())
+                        .ok_or_else(|| {
+                            Error::InvalidArgument("--threshold expects a percentage".to_owned())
+                        })?;
+                    options.threshold = Some(threshold);
+                }
+                _ if arg.starts_with("--") => {
+                    return Err(Error::InvalidArgument(format!("unknown option {}", arg)))
+                }
+                _ => options.days.push(arg.to_owned()),
+            }
+        }
+        options.validate()?;
+        Ok(options)
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.command != Command::Run {
+            if !self.days.is_empty() {
+                return Err(Error::InvalidArgument(
+                    "commands accept a single day".to_owned(),
+                ));
+            }
+            if self.input.is_some() || self.profile.is_some() || self.all_profiles {
+                return Err(Error::InvalidArgument(
+                    "commands only use the default input".to_owned(),
+                ));
+            }
+        }
+        if (self.redact || self.update_readme) && self.format != Format::Markdown {
+            return Err(Error::InvalidArgument(
+                "--redact and --update-readme require --markdown".to_owned(),
+            ));
+        }
+        if self.threshold.is_some() && self.compare.is_none() {
+            return Err(Error::InvalidArgument(
+                "--threshold requires --compare".to_owned(),
+            ));
+        }
+        if self.isolated && self.jobs < 2 {
+            return Err(Error::InvalidArgument(
+                "--isolated requires --jobs with more than one job".to_owned(),
+            ));
+        }
+        if self.bench.is_some() && self.jobs > 1 && !self.isolated {
+            return Err(Error::InvalidArgument(
+                "--bench with --jobs requires --isolated".to_owned(),
+            ));
+        }
+        let conflict = if self.bench.is_some() && self.timeout.is_some() {
+            Some("--bench and --timeout")
+        } else if self.input.is_some() && self.record {
+            Some("--input and --record")
+        } else if self.input.is_some() && (self.profile.is_some() || self.all_profiles) {
+            Some("--input and profiles")
+        } else if self.input.is_some() && self.wait {
+            Some("--input and --wait")
+        } else if self.input.is_some() && self.refresh {
+            Some("--input and --refresh")
+        } else if self.watch && self.refresh {
+            Some("--watch and --refresh")
+        } else if self.watch && self.input.as_deref() == Some(Path::new("-")) {
+            Some("--watch and --input -")
+        } else if self.watch && self.format != Format::Text {
+            Some("--watch and --format")
+        } else if self.input.is_some() && self.has_baseline() {
+            Some("--input and baselines")
+        } else if self.watch && self.has_baseline() {
+            Some("--watch and baselines")
+        } else if self.compare.is_some() && self.format != Format::Text {
+            Some("--compare and --format")
+        } else if self.variants && (self.watch || self.has_baseline()) {
+            Some("--variants and --watch or baselines")
+        } else if self.variants && (self.record || self.timeout.is_some()) {
+            Some("--variants and --record or --timeout")
+        } else if self.variants && self.format != Format::Text {
+            Some("--variants and --format")
+        } else if self.jobs > 1 && (self.watch || self.variants) {
+            Some("--jobs and --watch or --variants")
+        } else if self.profile.is_some() && self.all_profiles {
+            Some("--profile and --all-profiles")
+        } else {
+            None
+        };
+        match conflict {
+            Some(conflict) => Err(Error::InvalidArgument(format!(
+                "{} cannot be combined",
+                conflict
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether the timings of this run are stored or compared as a baseline.
+    pub fn has_baseline(&self) -> bool {
+        self.save_baseline.is_some() || self.compare.is_some()
+    }
+
+    pub fn is_day_included(&self, year: u32, module_name: &str, default_year: u32) -> bool {
+        self.days.is_empty()
+            || self
+                .days
+                .iter()
+                .any(|day| parse_day_selector(day, default_year) == Some((year, module_name)))
+    }
+}
+
+/// Splits a day selector such as `2019:day05` into its year and module name.
+/// Selectors without a year, such as `day05`, refer to the default year.
+pub fn parse_day_selector(selector: &str, default_year: u32) -> Option<(u32, &str)> {
+    let mut split = selector.splitn(2, ':');
+    match (split.next(), split.next()) {
+        (Some(year), Some(module_name)) => Some((year.parse().ok()?, module_name)),
+        (Some(module_name), None) => Some((default_year, module_name)),
+        _ => None,
+    }
+}
+
+/// Takes the day argument of a command, such as `day07`.
+fn parse_day<'a>(args: &mut impl Iterator<Item = &'a str>) -> Option<String> {
+    args.next()
+        .filter(|day| !day.starts_with("--"))
+        .map(str::to_owned)
+}
+
+fn parse_count(option: &str, value: Option<&str>) -> Result<usize, Error> {
+    value
+        .and_then(|value| value.parse().ok())
+        .filter(|&count| count > 0)
+        .ok_or_else(|| Error::InvalidArgument(format!("{} expects a positive number", option)))
+}
+
+fn parse_seconds(option: &str, value: Option<&str>) -> Result<Duration, Error> {
+    value
+        .and_then(|value| value.parse::<f64>().ok())
+        .filter(|&seconds| seconds > 0.0 && seconds.is_finite())
+        .map(Duration::from_secs_f64)
+        .ok_or_else(|| {
+            Error::InvalidArgument(format!("{} expects a positive number of seconds", option))
+        })
+}
+
+/// Parses the name of a baseline or profile, which is used as a file name.
+fn parse_name(option: &str, value: Option<&str>) -> Result<String, Error> {
+    value
+        .filter(|name| {
+            !name.is_empty() && !name.starts_with('.') && !name.contains(&['/', '\\'][..])
+        })
+        .map(str::to_owned)
+        .ok_or_else(|| Error::InvalidArgument(format!("{} expects a name", option)))
+}
+
+/// Parses the name of a profile, which cannot be a number, as the inputs of
+/// other years are stored in directories named after the year.
+fn parse_profile(option: &str, value: Option<&str>) -> Result<String, Error> {
+    let name = parse_name(option, value)?;
+    if name.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(Error::InvalidArgument(format!(
+            "{} expects a name that is not a year",
+            option
+        )));
+    }
+    Ok(name)
+}
+
+fn parse_value<T: FromStr<Err = Error>>(option: &str, value: Option<&str>) -> Result<T, Error> {
+    value
+        .ok_or_else(|| Error::InvalidArgument(format!("{} expects a value", option)))?
+        .parse()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse_error(args: &[&str]) -> String {
+        Options::parse(args).unwrap_err().to_string()
+    }
+
+    fn assert_conflict(args: &[&str], conflict: &str) {
+        assert_eq!(
+            parse_error(args),
+            format!("invalid argument: {} cannot be combined", conflict)
+        );
+    }
+
+    #[test]
+    fn bench() {
+        let options = Options::parse(&["day07", "--bench", "10"]).unwrap();
+        assert_eq!(options.days, vec!["day07"]);
+        assert_eq!(options.bench, Some(10));
+
+        for args in &[&["--bench", "0"][..], &["--bench", "x"], &["--bench"]] {
+            assert_eq!(
+                parse_error(args),
+                "invalid argument: --bench expects a positive number"
+            );
+        }
+        assert_eq!(
+            parse_error(&["--nope"]),
+            "invalid argument: unknown option --nope"
+        );
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(Options::parse(&[]).unwrap().format, Format::Text);
+        let options = Options::parse(&["--format", "csv"]).unwrap();
+        assert_eq!(options.format, Format::Csv);
+        assert_eq!(
+            parse_error(&["--format", "xml"]),
+            "invalid argument: unknown format xml"
+        );
+        assert_eq!(
+            parse_error(&["--format"]),
+            "invalid argument: --format expects a value"
+        );
+    }
+
+    #[test]
+    fn timeout() {
+        let options = Options::parse(&["--timeout", "1.5"]).unwrap();
+        assert_eq!(options.timeout, Some(Duration::from_millis(1500)));
+
+        for seconds in &["0", "-1", "inf", "NaN"] {
+            assert_eq!(
+                parse_error(&["--timeout", seconds]),
+                "invalid argument: --timeout expects a positive number of seconds"
+            );
+        }
+        assert_conflict(&["--bench", "5", "--timeout", "1"], "--bench and --timeout");
+    }
+
+    #[test]
+    fn jobs() {
+        assert_eq!(Options::parse(&[]).unwrap().jobs, 1);
+        let options = Options::parse(&["day07", "--jobs", "4", "--isolated", "day05"]).unwrap();
+        assert_eq!(options.days, vec!["day07", "day05"]);
+        assert_eq!((options.jobs, options.isolated), (4, true));
+
+        assert_eq!(
+            parse_error(&["--jobs", "-1"]),
+            "invalid argument: --jobs expects a positive number"
+        );
+        for args in &[&["--isolated"][..], &["--isolated", "--jobs", "1"]] {
+            assert_eq!(
+                parse_error(args),
+                "invalid argument: --isolated requires --jobs with more than one job"
+            );
+        }
+
+        assert!(Options::parse(&["--jobs", "4", "--isolated", "--bench", "5"]).is_ok());
+        assert!(Options::parse(&["--jobs", "1", "--bench", "5"]).is_ok());
+        assert_eq!(
+            parse_error(&["--jobs", "4", "--bench", "5"]),
+            "invalid argument: --bench with --jobs requires --isolated"
+        );
+        assert_conflict(
+            &["--jobs", "4", "--watch"],
+            "--jobs and --watch or --variants",
+        );
+        assert_conflict(
+            &["--jobs", "4", "--variants"],
+            "--jobs and --watch or --variants",
+        );
+    }
+
+    #[test]
+    fn input() {
+        let options = Options::parse(&["day07", "--input", "-"]).unwrap();
+        assert_eq!(options.input, Some(PathBuf::from("-")));
+        assert_eq!(
+            parse_error(&["--input"]),
+            "invalid argument: --input expects a path"
+        );
+    }
+
+    #[test]
+    fn profiles() {
+        let options = Options::parse(&["--profile", "team alice"]).unwrap();
+        assert_eq!(options.profile.as_deref(), Some("team alice"));
+        assert!(Options::parse(&["--all-profiles"]).unwrap().all_profiles);
+        for name in &["", ".hidden", "a/b", "a\\b", "../../etc"] {
+            assert_eq!(
+                parse_error(&["--profile", name]),
+                "invalid argument: --profile expects a name"
+            );
+        }
+        for name in &["2019", "0"] {
+            assert_eq!(
+                parse_error(&["--profile", name]),
+                "invalid argument: --profile expects a name that is not a year"
+            );
+        }
+
+        assert_conflict(&["--input", "x", "--record"], "--input and --record");
+        assert_conflict(&["--input", "x", "--profile", "a"], "--input and profiles");
+        assert_conflict(&["--input", "x", "--all-profiles"], "--input and profiles");
+        assert_conflict(
+            &["--profile", "a", "--all-profiles"],
+            "--profile and --all-profiles",
+        );
+    }
+
+    #[test]
+    fn wait() {
+        assert!(Options::parse(&["day07", "--wait"]).unwrap().wait);
+        assert_conflict(&["--input", "x", "--wait"], "--input and --wait");
+    }
+
+    #[test]
+    fn submit() {
+        assert_eq!(Options::parse(&[]).unwrap().command, Command::Run);
+        assert_eq!(
+            Options::parse(&["submit", "day05", "pt2"]).unwrap().command,
+            Command::Submit {
+                day: "day05".to_owned(),
+                part: 2
+            }
+        );
+
+        let usage = "invalid argument: usage: submit dayXX pt1|pt2";
+        assert_eq!(parse_error(&["submit", "day07"]), usage);
+        assert_eq!(parse_error(&["submit", "day07", "pt3"]), usage);
+        assert_eq!(parse_error(&["submit", "--record"]), usage);
+    }
+
+    #[test]
+    fn examples() {
+        assert_eq!(
+            Options::parse(&["examples", "day07"]).unwrap().command,
+            Command::Examples {
+                day: "day07".to_owned()
+            }
+        );
+        assert_eq!(
+            parse_error(&["examples"]),
+            "invalid argument: usage: examples dayXX"
+        );
+        assert_eq!(
+            parse_error(&["examples", "day07", "day08"]),
+            "invalid argument: commands accept a single day"
+        );
+        assert_eq!(
+            parse_error(&["submit", "day07", "pt1", "--profile", "alice"]),
+            "invalid argument: commands only use the default input"
+        );
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            Options::parse(&["new", "day07"]).unwrap().command,
+            Command::New {
+                day: "day07".to_owned()
+            }
+        );
+        assert_eq!(parse_error(&["new"]), "invalid argument: usage: new dayXX");
+        assert_eq!(
+            parse_error(&["new", "day07", "day08"]),
+            "invalid argument: commands accept a single day"
+        );
+    }
+
+    #[test]
+    fn years() {
+        let options = Options::parse(&["day07", "2019:day05"]).unwrap();
+        assert!(options.is_day_included(2020, "day07", 2020));
+        assert!(options.is_day_included(2019, "day05", 2020));
+        assert!(!options.is_day_included(2020, "day05", 2020));
+        assert!(Options::parse(&[])
+            .unwrap()
+            .is_day_included(2019, "day05", 2020));
+
+        assert_eq!(
+            parse_day_selector("2019:day05", 2020),
+            Some((2019, "day05"))
+        );
+        assert_eq!(parse_day_selector("day05", 2020), Some((2020, "day05")));
+        assert_eq!(parse_day_selector("x:day05", 2020), None);
+
+        assert_eq!(
+            Options::parse(&["submit", "2019:day05", "pt2"])
+                .unwrap()
+                .command,
+            Command::Submit {
+                day: "2019:day05".to_owned(),
+                part: 2
+            }
+        );
+    }
+
+    #[test]
+    fn refresh() {
+        assert!(Options::parse(&["day07", "--refresh"]).unwrap().refresh);
+        assert_conflict(&["--input", "x", "--refresh"], "--input and --refresh");
+    }
+
+    #[test]
+    fn watch() {
+        assert!(Options::parse(&["--watch", "--input", "x", "day07"]).is_ok());
+        assert_conflict(&["--watch", "--input", "-"], "--watch and --input -");
+        assert_conflict(&["--watch", "--refresh"], "--watch and --refresh");
+        assert_conflict(&["--watch", "--format", "json"], "--watch and --format");
+    }
+
+    #[test]
+    fn baselines() {
+        let options = Options::parse(&["--compare", "a", "--threshold", "5%"]).unwrap();
+        assert_eq!(options.compare.as_deref(), Some("a"));
+        assert_eq!(options.threshold, Some(5.0));
+        let options = Options::parse(&["--save-baseline", "b"]).unwrap();
+        assert_eq!(options.save_baseline.as_deref(), Some("b"));
+
+        for name in &["", ".hidden", "a/b", "a\\b", "../../etc"] {
+            assert_eq!(
+                parse_error(&["--save-baseline", name]),
+                "invalid argument: --save-baseline expects a name"
+            );
+        }
+        assert_eq!(
+            parse_error(&["--compare"]),
+            "invalid argument: --compare expects a name"
+        );
+        assert_eq!(
+            parse_error(&["--compare", "a", "--threshold", "-5"]),
+            "invalid argument: --threshold expects a percentage"
+        );
+        assert_eq!(
+            parse_error(&["--threshold", "5"]),
+            "invalid argument: --threshold requires --compare"
+        );
+
+        assert_conflict(
+            &["--input", "x", "--save-baseline", "b"],
+            "--input and baselines",
+        );
+        assert_conflict(&["--watch", "--compare", "b"], "--watch and baselines");
+        assert_conflict(
+            &["--compare", "b", "--format", "csv"],
+            "--compare and --format",
+        );
+    }
+
+    #[test]
+    fn markdown() {
+        let options = Options::parse(&["--markdown", "--redact", "--update-readme"]).unwrap();
+        assert_eq!(options.format, Format::Markdown);
+        assert!(options.redact && options.update_readme);
+
+        for option in &["--redact", "--update-readme"] {
+            assert_eq!(
+                parse_error(&[option]),
+                "invalid argument: --redact and --update-readme require --markdown"
+            );
+        }
+    }
+
+    #[test]
+    fn variants() {
+        assert!(Options::parse(&["--variants", "--bench", "5"]).is_ok());
+        let conflicts: &[(&[&str], &str)] = &[
+            (
+                &["--variants", "--watch"],
+                "--variants and --watch or baselines",
+            ),
+            (
+                &["--variants", "--save-baseline", "b"],
+                "--variants and --watch or baselines",
+            ),
+            (
+                &["--variants", "--record"],
+                "--variants and --record or --timeout",
+            ),
+            (
+                &["--variants", "--timeout", "1"],
+                "--variants and --record or --timeout",
+            ),
+            (&["--variants", "--markdown"], "--variants and --format"),
+        ];
+        for (args, conflict) in conflicts {
+            assert_conflict(args, conflict);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use crate::error::Error;
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+};
+
+thread_local! {
+    static PANIC_LOCATION: RefCell<Option<String>> = RefCell::new(None);
+    /// Whether the current thread is running a function inside of `catch`.
+    static IS_CATCHING: Cell<bool> = Cell::new(false);
+}
+
+/// Wraps the panic hook, so that panics inside of `catch` are not printed, but
+/// only remember where they occurred, so it can be included in the error.
+/// Other panics are passed on to the previous hook. Only installs the hook
+/// once.
+pub fn install_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !IS_CATCHING.with(Cell::get) {
+                return previous_hook(info);
+            }
+            let location = info
+                .location()
+                .map(|location| format!("{}:{}", location.file(), location.line()));
+            PANIC_LOCATION.with(|cell| *cell.borrow_mut() = location);
+        }));
+    });
+}
+
+/// Runs a function, turning a panic into an `Error::Panic`.
+pub fn catch<T, F: FnOnce() -> Result<T, Error>>(f: F) -> Result<T, Error> {
+    PANIC_LOCATION.with(|cell| *cell.borrow_mut() = None);
+    let was_catching = IS_CATCHING.with(|cell| cell.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    IS_CATCHING.with(|cell| cell.set(was_catching));
+    result.unwrap_or_else(|payload| {
+        let message = payload_message(&*payload);
+        let location = PANIC_LOCATION.with(|cell| cell.borrow_mut().take());
+        Err(Error::Panic(match location {
+            Some(location) => format!("{} at {}", message, location),
+            None => message,
+        }))
+    })
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_owned()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_owned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn catching() {
+        install_hook();
+        assert_eq!(catch(|| Ok(5)).unwrap(), 5);
+        assert!(matches!(
+            catch::<(), _>(|| Err(Error::NoSolution)),
+            Err(Error::NoSolution)
+        ));
+
+        let message = |result: Result<(), Error>| match result {
+            Err(Error::Panic(message)) => message,
+            result => panic!("expected a panic, got {:?}", result),
+        };
+        let str_message = message(catch(|| panic!("static message")));
+        assert!(str_message.starts_with("static message at "));
+        assert!(str_message.contains("panic.rs"));
+        let string_message = message(catch(|| panic!("formatted {}", 5)));
+        assert!(string_message.starts_with("formatted 5 at "));
+        assert!(!IS_CATCHING.with(Cell::get));
+    }
+}
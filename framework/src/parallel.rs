@@ -0,0 +1,115 @@
+use crate::error::Error;
+use std::{
+    collections::VecDeque,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+pub type Task<T> = Box<dyn FnOnce() -> T + Send>;
+
+/// Runs tasks on a pool of `jobs` worker threads, and passes their results to
+/// `handle` in the order in which the tasks were given, as soon as possible.
+/// If a task panics, the results of the tasks after it are not handled.
+pub fn run_in_order<T, F>(jobs: usize, tasks: Vec<Task<T>>, mut handle: F) -> Result<(), Error>
+where
+    T: Send + 'static,
+    F: FnMut(T) -> Result<(), Error>,
+{
+    let task_count = tasks.len();
+    let queue = Arc::new(Mutex::new(
+        tasks.into_iter().enumerate().collect::<VecDeque<_>>(),
+    ));
+    let (sender, receiver) = mpsc::channel();
+    for _ in 0..jobs.min(task_count) {
+        let queue = queue.clone();
+        let sender = sender.clone();
+        thread::spawn(move || loop {
+            let task = queue.lock().unwrap().pop_front();
+            match task {
+                Some((index, task)) => {
+                    if sender.send((index, task())).is_err() {
+                        break;
+                    }
+                }
+                None => break,
+            }
+        });
+    }
+    drop(sender);
+
+    let mut pending = (0..task_count).map(|_| None).collect::<Vec<_>>();
+    let mut next_index = 0;
+    for (index, result) in receiver {
+        pending[index] = Some(result);
+        while let Some(result) = pending.get_mut(next_index).and_then(Option::take) {
+            handle(result)?;
+            next_index += 1;
+        }
+    }
+    if next_index < task_count {
+        return Err(Error::Panic(format!(
+            "a worker thread panicked, {} result(s) are missing",
+            task_count - next_index
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn results_in_order() {
+        // Later tasks finish first.
+        let tasks = (0..4u64)
+            .map(|index| {
+                Box::new(move || {
+                    thread::sleep(Duration::from_millis(40 - index * 10));
+                    index
+                }) as Task<_>
+            })
+            .collect();
+        let mut results = Vec::new();
+        run_in_order(4, tasks, |index| {
+            results.push(index);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(results, vec![0u64, 1, 2, 3]);
+
+        let tasks = (0..4)
+            .map(|index| Box::new(move || index) as Task<_>)
+            .collect();
+        let result = run_in_order(2, tasks, |index| {
+            if index == 1 {
+                Err(Error::NoSolution)
+            } else {
+                Ok(())
+            }
+        });
+        assert!(matches!(result, Err(Error::NoSolution)));
+    }
+
+    #[test]
+    fn panicking_tasks() {
+        let tasks = (0..4)
+            .map(|index| {
+                Box::new(move || {
+                    if index == 2 {
+                        panic!("task failed");
+                    }
+                    index
+                }) as Task<_>
+            })
+            .collect();
+        let mut results = Vec::new();
+        let result = run_in_order(2, tasks, |index: i32| {
+            results.push(index);
+            Ok(())
+        });
+        assert!(matches!(result, Err(Error::Panic(_))));
+        assert_eq!(results, vec![0, 1]);
+    }
+}
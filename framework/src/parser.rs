@@ -117,7 +117,7 @@ fn take_unsigned<T: PrimIntExt + Unsigned>(mut input: &str) -> Result<(&str, T),
     let mut nr = T::zero();
     let original_length = input.len();
     while let Some(char) = input.chars().next() {
-        if char < '0' || char > '9' {
+        if !('0'..='9').contains(&char) {
             break;
         }
         let digit = char as u8 - b'0';
@@ -157,7 +157,7 @@ fn take_signed<T: PrimIntExt + Signed>(input: &str) -> Result<(&str, T), TakeInt
     if is_negative {
         unsigned = !unsigned;
     }
-    return Ok((remainder, T::from_unsigned(unsigned)));
+    Ok((remainder, T::from_unsigned(unsigned)))
 }
 
 #[cfg(test)]
@@ -0,0 +1,250 @@
+//! Side-by-side comparison of the results of a day across the inputs of every
+//! profile, as printed by `--all-profiles`.
+
+use crate::{
+    answers::Verdict,
+    report::{day_label, format_duration, shown_results, DayReport},
+};
+use colored::Colorize;
+
+/// The name shown for the default input, which does not belong to a profile.
+const DEFAULT_PROFILE: &str = "default";
+
+/// A cell of the table, along with whether it marks a disagreement.
+type Cell = (String, bool);
+
+/// The cells of a single evaluated input: the name of the profile, followed by
+/// the answer and the duration of every part. The answer disagrees if the part
+/// failed, or if it differs from the recorded answer for that input.
+struct Row {
+    profile: String,
+    cells: Vec<Cell>,
+}
+
+impl Row {
+    fn new(report: &DayReport) -> Row {
+        let mut cells = Vec::new();
+        for (index, (_, result, duration, _)) in
+            shown_results(&report.evaluation).into_iter().enumerate()
+        {
+            let answer = match result {
+                Ok(value) => {
+                    let value = value.replace('\n', " / ");
+                    match report.verdicts.get(index).cloned().flatten() {
+                        Some(Verdict::Correct) => (format!("{} ✓", value), false),
+                        Some(Verdict::Wrong { expected }) => {
+                            (format!("{} ✗ expected {}", value, expected), true)
+                        }
+                        Some(Verdict::New) => (format!("{} new", value), false),
+                        None => (value, false),
+                    }
+                }
+                Err(err) => (err, true),
+            };
+            cells.push(answer);
+            cells.push((format_duration(duration), false));
+        }
+        Row {
+            profile: report
+                .profile
+                .clone()
+                .unwrap_or_else(|| DEFAULT_PROFILE.to_owned()),
+            cells,
+        }
+    }
+
+    fn disagrees(&self) -> bool {
+        self.cells.iter().any(|&(_, disagrees)| disagrees)
+    }
+}
+
+/// Collects the results of every input of every day, to print them as a table
+/// per day once the run is over.
+#[derive(Default)]
+pub struct Comparison {
+    /// The year and number of each day, with a row per input, in the order in
+    /// which they were evaluated.
+    days: Vec<(u32, u32, Vec<Row>)>,
+    /// The names of the parts of each day, as found in its reports.
+    part_names: Vec<Vec<&'static str>>,
+}
+
+impl Comparison {
+    pub fn add(&mut self, report: &DayReport) {
+        let row = Row::new(report);
+        let names = report
+            .evaluation
+            .parts
+            .iter()
+            .map(|part| part.name)
+            .collect::<Vec<_>>();
+        match self
+            .days
+            .iter()
+            .position(|&(year, day_nr, _)| (year, day_nr) == (report.year, report.day_nr))
+        {
+            Some(index) => {
+                self.days[index].2.push(row);
+                // The inputs that failed to parse do not name the parts.
+                if self.part_names[index].len() < names.len() {
+                    self.part_names[index] = names;
+                }
+            }
+            None => {
+                self.days.push((report.year, report.day_nr, vec![row]));
+                self.part_names.push(names);
+            }
+        }
+    }
+
+    /// Prints a table for every day that was evaluated with more than one
+    /// input, with a row per input.
+    pub fn print(&self, default_year: u32) {
+        for ((year, day_nr, rows), part_names) in self.days.iter().zip(&self.part_names) {
+            if rows.len() < 2 {
+                continue;
+            }
+            let disagreeing = rows.iter().filter(|row| row.disagrees()).count();
+            let counts = format!("{} inputs, {} disagreeing", rows.len(), disagreeing);
+            println!(
+                "{} ({})",
+                day_label(*year, default_year, *day_nr).bright_blue(),
+                if disagreeing > 0 {
+                    counts.bright_red().bold()
+                } else {
+                    counts.bright_white()
+                }
+            );
+
+            let mut header = vec!["input".to_owned()];
+            for name in part_names {
+                header.push((*name).to_owned());
+                header.push("ms".to_owned());
+            }
+            let column_count = rows
+                .iter()
+                .map(|row| row.cells.len() + 1)
+                .chain(std::iter::once(header.len()))
+                .max()
+                .unwrap_or(0);
+            let mut widths = vec![0; column_count];
+            for (index, title) in header.iter().enumerate() {
+                widths[index] = title.chars().count();
+            }
+            for row in rows {
+                widths[0] = widths[0].max(row.profile.chars().count());
+                for (index, (text, _)) in row.cells.iter().enumerate() {
+                    widths[index + 1] = widths[index + 1].max(text.chars().count());
+                }
+            }
+
+            let header = header
+                .iter()
+                .enumerate()
+                .map(|(index, title)| pad(title, widths[index], index))
+                .collect::<Vec<_>>()
+                .join("  ");
+            println!("  {}", header.bright_green());
+            for row in rows {
+                let mut line = pad(&row.profile, widths[0], 0).bright_white().to_string();
+                for (index, (text, disagrees)) in row.cells.iter().enumerate() {
+                    let text = pad(text, widths[index + 1], index + 1);
+                    line.push_str("  ");
+                    line.push_str(
+                        &if *disagrees {
+                            text.bright_red().bold()
+                        } else {
+                            text.normal()
+                        }
+                        .to_string(),
+                    );
+                }
+                println!("  {}", line);
+            }
+        }
+    }
+}
+
+/// Pads the text of a column, where the durations in every other column after
+/// the first are right-aligned, and everything else is left-aligned.
+fn pad(text: &str, width: usize, column: usize) -> String {
+    if column > 0 && column % 2 == 0 {
+        format!("{:>width$}", text, width = width)
+    } else {
+        format!("{:<width$}", text, width = width)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::traits::{Evaluation, Phase};
+    use arrayvec::ArrayVec;
+    use std::time::Duration;
+
+    fn report(
+        profile: Option<&str>,
+        answers: &[&str],
+        verdicts: Vec<Option<Verdict>>,
+    ) -> DayReport {
+        let mut evaluation = Evaluation::new(Phase {
+            name: "parse",
+            result: Ok(()),
+            duration: Duration::default(),
+            allocations: None,
+        });
+        for (answer, name) in answers.iter().zip(&["pt1", "pt2"]) {
+            evaluation.parts.push(Phase {
+                name,
+                result: Ok((*answer).to_owned()),
+                duration: Duration::from_millis(2),
+                allocations: None,
+            });
+        }
+        DayReport {
+            year: 2020,
+            day_nr: 7,
+            profile: profile.map(str::to_owned),
+            evaluation,
+            stats: None,
+            verdicts: verdicts.into_iter().collect::<ArrayVec<_>>(),
+        }
+    }
+
+    #[test]
+    fn rows() {
+        let mut comparison = Comparison::default();
+        comparison.add(&report(
+            None,
+            &["1", "2"],
+            vec![Some(Verdict::Correct), Some(Verdict::New)],
+        ));
+        comparison.add(&report(
+            Some("alice"),
+            &["3", "4\n5"],
+            vec![
+                Some(Verdict::Wrong {
+                    expected: "4".to_owned(),
+                }),
+                None,
+            ],
+        ));
+        assert_eq!(comparison.days.len(), 1);
+        assert_eq!(comparison.part_names, vec![vec!["pt1", "pt2"]]);
+
+        let rows = &comparison.days[0].2;
+        assert_eq!(rows[0].profile, "default");
+        assert!(!rows[0].disagrees());
+        assert_eq!(rows[0].cells[0], ("1 ✓".to_owned(), false));
+        assert_eq!(rows[0].cells[2], ("2 new".to_owned(), false));
+
+        assert_eq!(rows[1].profile, "alice");
+        assert!(rows[1].disagrees());
+        assert_eq!(rows[1].cells[0], ("3 ✗ expected 4".to_owned(), true));
+        assert_eq!(
+            rows[1].cells[1],
+            (format_duration(Duration::from_millis(2)), false)
+        );
+        assert_eq!(rows[1].cells[2], ("4 / 5".to_owned(), false));
+    }
+}
@@ -0,0 +1,294 @@
+use crate::{
+    answers::{escape, unescape},
+    config::Config,
+    error::Error,
+    http::{self, RequestThrottle, RetryPolicy},
+    input::{self, SessionKey},
+};
+use std::{fs, io, path::PathBuf};
+
+/// Marks the start of the second part in a puzzle description, which is only
+/// present once the first part has been solved.
+const PART_TWO_MARKER: &str = "id=\"part2\"";
+
+/// Returns the directory in which the description and examples of a day are
+/// stored.
+pub fn get_puzzle_dir(config: &Config, year: u32, day_nr: u32) -> PathBuf {
+    config
+        .year_dir(&config.examples_dir, year)
+        .join(format!("day{:0>2}", day_nr))
+}
+
+fn get_example_path(config: &Config, year: u32, day_nr: u32, index: usize) -> PathBuf {
+    get_puzzle_dir(config, year, day_nr).join(format!("{}.txt", index))
+}
+
+fn get_highlights_path(config: &Config, year: u32, day_nr: u32) -> PathBuf {
+    get_puzzle_dir(config, year, day_nr).join("highlights.txt")
+}
+
+/// Returns the puzzle description of a day, from the cache, or by downloading
+/// it. Descriptions without the second part are downloaded again, as it may
+/// have been unlocked since.
+fn get_puzzle_html(
+    config: &Config,
+    throttle: &mut RequestThrottle,
+    session_key: &mut SessionKey,
+    year: u32,
+    day_nr: u32,
+) -> Result<String, Error> {
+    let file_path = get_puzzle_dir(config, year, day_nr).join("puzzle.html");
+    if let Ok(contents) = fs::read_to_string(&file_path) {
+        if contents.contains(PART_TWO_MARKER) {
+            return Ok(contents);
+        }
+    }
+
+    let url = format!("{}/{}/day/{}", config.base_url, year, day_nr);
+    let session_key = input::get_session_key(config, session_key)?;
+    let contents = http::get(throttle, &RetryPolicy::default(), &url, session_key)?;
+    fs::create_dir_all(get_puzzle_dir(config, year, day_nr))?;
+    fs::write(file_path, &contents)?;
+    Ok(contents)
+}
+
+/// Extracts the examples and answer highlights from the puzzle description of
+/// a day, and stores them as `0.txt`, `1.txt`, ... and `highlights.txt`,
+/// replacing the examples stored before. Returns the number of examples and highlights.
+pub fn update_examples(
+    config: &Config,
+    throttle: &mut RequestThrottle,
+    session_key: &mut SessionKey,
+    year: u32,
+    day_nr: u32,
+) -> Result<(usize, usize), Error> {
+    let html = get_puzzle_html(config, throttle, session_key, year, day_nr)?;
+    let examples = extract_examples(&html);
+    for (_, path) in list_examples(config, year, day_nr)? {
+        fs::remove_file(path)?;
+    }
+    for (index, example) in examples.iter().enumerate() {
+        fs::write(get_example_path(config, year, day_nr, index), example)?;
+    }
+    let highlights = extract_highlights(&html);
+    let contents = highlights
+        .iter()
+        .map(|(part, value)| format!("pt{} = {}\n", part, escape(value)))
+        .collect::<String>();
+    fs::write(get_highlights_path(config, year, day_nr), contents)?;
+    Ok((examples.len(), highlights.len()))
+}
+
+/// Lists the examples of a day, as stored by the `examples` command, by index.
+pub fn list_examples(
+    config: &Config,
+    year: u32,
+    day_nr: u32,
+) -> Result<Vec<(usize, PathBuf)>, Error> {
+    let entries = match fs::read_dir(get_puzzle_dir(config, year, day_nr)) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    let mut examples = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let index = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".txt"))
+            .and_then(|index| index.parse::<usize>().ok());
+        if let Some(index) = index {
+            examples.push((index, path));
+        }
+    }
+    examples.sort();
+    Ok(examples)
+}
+
+pub fn load_example(
+    config: &Config,
+    year: u32,
+    day_nr: u32,
+    index: usize,
+) -> Result<String, Error> {
+    Ok(fs::read_to_string(get_example_path(
+        config, year, day_nr, index,
+    ))?)
+}
+
+/// Loads the highlights of one part of a day, in the order they appear in.
+pub fn load_highlights(
+    config: &Config,
+    year: u32,
+    day_nr: u32,
+    part: usize,
+) -> Result<Vec<String>, Error> {
+    let contents = fs::read_to_string(get_highlights_path(config, year, day_nr))?;
+    let mut highlights = Vec::new();
+    for line in contents.lines() {
+        let invalid_line = || Error::InvalidInputDyn(format!("invalid highlight line: {}", line));
+        let mut split = line.splitn(2, " = ");
+        let line_part = split
+            .next()
+            .and_then(|part| part.strip_prefix("pt"))
+            .and_then(|nr| nr.parse::<usize>().ok())
+            .ok_or_else(invalid_line)?;
+        let value = split.next().and_then(unescape).ok_or_else(invalid_line)?;
+        if line_part == part {
+            highlights.push(value);
+        }
+    }
+    Ok(highlights)
+}
+
+/// Returns the contents of every `<pre><code>` block, without the trailing
+/// line break, the same as inputs.
+pub fn extract_examples(html: &str) -> Vec<String> {
+    find_elements(html, "<pre><code>", "</code></pre>")
+        .map(|inner| {
+            let mut example = text_content(inner);
+            if example.ends_with('\n') {
+                example.pop();
+            }
+            example
+        })
+        .collect()
+}
+
+/// Returns the emphasized code snippets, which is how answers to the examples
+/// are highlighted, along with the part of the puzzle they are in.
+pub fn extract_highlights(html: &str) -> Vec<(usize, String)> {
+    html.split("<article")
+        .skip(1)
+        .enumerate()
+        .flat_map(|(index, article)| {
+            let article = article.split("</article>").next().unwrap_or(article);
+            find_elements(article, "<code><em>", "</em></code>")
+                .map(move |inner| (index + 1, text_content(inner)))
+        })
+        .collect()
+}
+
+/// Iterates over the HTML between every pair of opening and closing tags.
+fn find_elements<'s>(
+    html: &'s str,
+    open: &'s str,
+    close: &'s str,
+) -> impl Iterator<Item = &'s str> + 's {
+    let mut remainder = html;
+    std::iter::from_fn(move || {
+        let start = remainder.find(open)? + open.len();
+        let end = start + remainder[start..].find(close)?;
+        let inner = &remainder[start..end];
+        remainder = &remainder[end + close.len()..];
+        Some(inner)
+    })
+}
+
+/// Removes the tags from HTML, and decodes the entities in the text.
+fn text_content(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::http::test_server::{serve_with_config, TestDir};
+    use std::{env, process};
+
+    /// A hand-written puzzle description, with a made-up second example.
+    const PUZZLE: &str = include_str!("../fixtures/day01.html");
+
+    #[test]
+    fn examples() {
+        assert_eq!(
+            extract_examples(PUZZLE),
+            vec![
+                "1721\n979\n366\n299\n675\n1456".to_owned(),
+                "<12> & \"34\"".to_owned(),
+            ]
+        );
+        assert!(extract_examples("<pre><code>unclosed").is_empty());
+    }
+
+    #[test]
+    fn highlights() {
+        assert_eq!(
+            extract_highlights(PUZZLE),
+            vec![(1, "514579".to_owned()), (2, "241861950".to_owned())]
+        );
+    }
+
+    #[test]
+    fn updates() {
+        let (server, _dir, config, mut throttle, mut session_key) =
+            serve_with_config("examples", vec![(200, PUZZLE)]);
+        let puzzle_dir = get_puzzle_dir(&config, 2020, 1);
+        fs::create_dir_all(&puzzle_dir).unwrap();
+        fs::write(puzzle_dir.join("2.txt"), "stale").unwrap();
+
+        for _ in 0..2 {
+            assert_eq!(
+                update_examples(&config, &mut throttle, &mut session_key, 2020, 1).unwrap(),
+                (2, 2)
+            );
+        }
+        // The description contains the second part, so it is only downloaded
+        // once.
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(
+            list_examples(&config, 2020, 1)
+                .unwrap()
+                .into_iter()
+                .map(|(index, _)| index)
+                .collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        assert_eq!(load_example(&config, 2020, 1, 1).unwrap(), "<12> & \"34\"");
+        assert_eq!(
+            load_highlights(&config, 2020, 1, 1).unwrap(),
+            vec!["514579"]
+        );
+        assert_eq!(
+            load_highlights(&config, 2020, 1, 2).unwrap(),
+            vec!["241861950"]
+        );
+    }
+
+    #[test]
+    fn highlight_escaping() {
+        let dir = TestDir(env::temp_dir().join(format!("aoc-highlights-test-{}", process::id())));
+        let config = Config::with_base_dir(&dir.0);
+        fs::create_dir_all(get_puzzle_dir(&config, 2020, 1)).unwrap();
+        fs::write(
+            get_highlights_path(&config, 2020, 1),
+            format!(
+                "pt1 = {}\npt2 = 5\npt1 = {}\n",
+                escape("a\nb"),
+                escape(" c ")
+            ),
+        )
+        .unwrap();
+        assert_eq!(
+            load_highlights(&config, 2020, 1, 1).unwrap(),
+            vec!["a\nb", " c "]
+        );
+        fs::write(get_highlights_path(&config, 2020, 1), "pt1 5").unwrap();
+        assert!(load_highlights(&config, 2020, 1, 1).is_err());
+    }
+}
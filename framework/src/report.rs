@@ -0,0 +1,694 @@
+use crate::{
+    answers::Verdict,
+    bench::Statistics,
+    config::Config,
+    error::Error,
+    memory::Allocations,
+    options::Options,
+    traits::{Evaluation, ResultWhereValueIsErrorExt},
+};
+use arrayvec::ArrayVec;
+use colored::Colorize;
+use std::{collections::HashSet, fmt::Write, fs, path::PathBuf, str::FromStr, time::Duration};
+
+/// The format in which the runner prints its results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Colored, human-readable output.
+    Text,
+    /// One JSON object per line, per day.
+    Json,
+    /// A header row, followed by one row per day.
+    Csv,
+    /// A Markdown table with a row per day, followed by the totals.
+    Markdown,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Format::Text
+    }
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            "markdown" => Ok(Format::Markdown),
+            _ => Err(Error::InvalidArgument(format!("unknown format {}", s))),
+        }
+    }
+}
+
+/// Everything that is known about a single evaluated day.
+#[derive(Debug)]
+pub struct DayReport {
+    pub year: u32,
+    pub day_nr: u32,
+    /// The profile whose input was used, if not the default one.
+    pub profile: Option<String>,
+    pub evaluation: Evaluation,
+    /// Timing statistics, when running in benchmark mode.
+    pub stats: Option<Statistics>,
+    /// How each successfully computed part compares to the recorded answers.
+    pub verdicts: ArrayVec<[Option<Verdict>; 2]>,
+}
+
+/// Totals over all days in a run.
+#[derive(Debug, Default)]
+pub struct Summary {
+    /// The number of distinct days, however many inputs each was evaluated on.
+    pub days: usize,
+    pub succeeded: usize,
+    /// Parts that returned an error, and days whose input failed to parse.
+    pub failed: usize,
+    /// Parts whose answer differs from the recorded answer.
+    pub wrong: usize,
+    pub total_duration: Duration,
+    /// The year, day and duration of the slowest day.
+    pub slowest_day: Option<(u32, u32, Duration)>,
+    /// The year and number of every day counted in `days`.
+    seen_days: HashSet<(u32, u32)>,
+}
+
+impl Summary {
+    pub fn add(&mut self, report: &DayReport) {
+        let evaluation = &report.evaluation;
+        self.seen_days.insert((report.year, report.day_nr));
+        self.days = self.seen_days.len();
+        if evaluation.parse.result.is_err() {
+            self.failed += 1;
+        }
+        for part in &evaluation.parts {
+            if part.result.is_ok() {
+                self.succeeded += 1;
+            } else {
+                self.failed += 1;
+            }
+        }
+        self.wrong += report
+            .verdicts
+            .iter()
+            .filter(|verdict| matches!(verdict, Some(Verdict::Wrong { .. })))
+            .count();
+
+        let duration = evaluation.total_duration();
+        self.total_duration += duration;
+        if self
+            .slowest_day
+            .map_or(true, |(_, _, slowest)| duration > slowest)
+        {
+            self.slowest_day = Some((report.year, report.day_nr, duration));
+        }
+    }
+}
+
+pub trait Reporter {
+    fn begin(&mut self) {}
+    fn report_day(&mut self, report: &DayReport);
+    fn finish(&mut self, _summary: &Summary) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+pub fn reporter(options: &Options, config: &Config) -> Box<dyn Reporter> {
+    let year = config.year;
+    match options.format {
+        Format::Text => Box::new(TextReporter { year }),
+        Format::Json => Box::new(JsonReporter),
+        Format::Csv => Box::new(CsvReporter),
+        Format::Markdown => Box::new(MarkdownReporter {
+            year,
+            redact: options.redact,
+            readme_file: if options.update_readme {
+                Some(config.readme_file.clone())
+            } else {
+                None
+            },
+            rows: Vec::new(),
+            totals: [Duration::default(); 4],
+        }),
+    }
+}
+
+/// Formats the name of a day, which includes the year unless it is the default
+/// year, such as `day07` or `2019:day05`.
+pub fn day_label(year: u32, default_year: u32, day_nr: u32) -> String {
+    if year == default_year {
+        format!("day{:0>2}", day_nr)
+    } else {
+        format!("{}:day{:0>2}", year, day_nr)
+    }
+}
+
+/// Formats a duration as milliseconds with two decimals, right-aligned.
+pub fn format_duration(duration: Duration) -> String {
+    let duration_nanos = duration.as_nanos();
+    let duration_ms = duration_nanos / 1_000_000;
+    let duration_decimals = duration_nanos / 1_000 % 1_000 / 10;
+    format!("{: >3}.{:0>2}", duration_ms, duration_decimals)
+}
+
+pub(crate) type ShownResult = (
+    &'static str,
+    Result<String, String>,
+    Duration,
+    Option<Allocations>,
+);
+
+/// Returns the phases whose results should be shown. That is the parser if it
+/// failed, and the parts otherwise.
+pub(crate) fn shown_results(evaluation: &Evaluation) -> ArrayVec<[ShownResult; 2]> {
+    match &evaluation.parse.result {
+        Err(err) => std::iter::once((
+            evaluation.parse.name,
+            Err(err.to_string()),
+            evaluation.parse.duration,
+            evaluation.parse.allocations,
+        ))
+        .collect(),
+        Ok(()) => evaluation
+            .parts
+            .iter()
+            .map(|part| {
+                let result = match &part.result {
+                    Ok(value) => Ok(value.clone()),
+                    Err(err) => Err(err.to_string()),
+                };
+                (part.name, result, part.duration, part.allocations)
+            })
+            .collect(),
+    }
+}
+
+/// Formats the allocations of a phase to follow its duration, if they are
+/// counted.
+fn format_allocations(allocations: Option<Allocations>) -> String {
+    match allocations {
+        Some(allocations) => format!(", {}", allocations.summary()),
+        None => String::new(),
+    }
+}
+
+struct TextReporter {
+    year: u32,
+}
+
+impl Reporter for TextReporter {
+    fn begin(&mut self) {
+        println!(
+            "{} {} {} {}",
+            "Advent".bright_red().bold(),
+            "of".bright_white(),
+            "Code".bright_green().bold(),
+            self.year.to_string().bright_blue()
+        );
+    }
+
+    fn report_day(&mut self, report: &DayReport) {
+        const VALUE_ALIGNMENT: usize = 16;
+
+        let evaluation = &report.evaluation;
+        let timing = match &report.stats {
+            Some(stats) => format!(
+                "min {} | median {} | mean {} | stddev {} ms, {} runs",
+                format_duration(stats.min),
+                format_duration(stats.median),
+                format_duration(stats.mean),
+                format_duration(stats.stddev),
+                stats.runs,
+            ),
+            None => format!("{} ms", format_duration(evaluation.total_duration())),
+        };
+        let results = shown_results(evaluation);
+        let use_expanded_format = results
+            .iter()
+            .any(|(_, result, _, _)| result.unwrap_either().contains('\n'));
+
+        let mut label = day_label(report.year, self.year, report.day_nr);
+        if let Some(profile) = &report.profile {
+            write!(label, " [{}]", profile).unwrap();
+        }
+        print!(
+            "{} ({}, parse {} ms{})",
+            label.bright_blue(),
+            timing.bright_white(),
+            format_duration(evaluation.parse.duration).bright_white(),
+            format_allocations(evaluation.parse.allocations),
+        );
+        if use_expanded_format {
+            println!();
+        } else {
+            print!(" |");
+        }
+
+        for (index, (pt_name, result, duration, allocations)) in results.into_iter().enumerate() {
+            let pt_ident = pt_name.bright_green();
+            let value = match result {
+                Ok(value) => value.bright_white().bold(),
+                Err(err) => err.bright_red().bold().underline(),
+            };
+            let duration = format!(
+                "({} ms{})",
+                format_duration(duration),
+                format_allocations(allocations)
+            )
+            .white();
+            let verdict = match report.verdicts.get(index).cloned().flatten() {
+                Some(Verdict::Correct) => " ✓".bright_green(),
+                Some(Verdict::Wrong { expected }) => {
+                    format!(" ✗ expected {}", expected).bright_red().bold()
+                }
+                Some(Verdict::New) => " new".yellow(),
+                None => "".normal(),
+            };
+            if use_expanded_format {
+                println!("{} {}{}\n{}", pt_ident, duration, verdict, value);
+            } else {
+                print!(
+                    " {} {:>width$} {}{} |",
+                    pt_ident,
+                    value,
+                    duration,
+                    verdict,
+                    width = VALUE_ALIGNMENT
+                );
+            }
+        }
+        if !use_expanded_format {
+            println!();
+        }
+    }
+
+    fn finish(&mut self, summary: &Summary) -> Result<(), Error> {
+        let failed = format!("{} failed", summary.failed);
+        let wrong = format!("{} wrong", summary.wrong);
+        print!(
+            "{} {} days | {} succeeded | {} | {} | total {} ms",
+            "summary".bright_blue(),
+            summary.days.to_string().bright_white(),
+            summary.succeeded.to_string().bright_green(),
+            if summary.failed > 0 {
+                failed.bright_red().bold()
+            } else {
+                failed.normal()
+            },
+            if summary.wrong > 0 {
+                wrong.bright_red().bold()
+            } else {
+                wrong.normal()
+            },
+            format_duration(summary.total_duration).bright_white(),
+        );
+        if let Some((year, day_nr, duration)) = summary.slowest_day {
+            print!(
+                " | slowest {} ({} ms)",
+                day_label(year, self.year, day_nr),
+                format_duration(duration).trim_start()
+            );
+        }
+        println!();
+        Ok(())
+    }
+}
+
+struct JsonReporter;
+
+/// Encodes a string as a JSON string literal, including the quotes.
+fn json_string(s: &str) -> String {
+    let mut res = String::with_capacity(s.len() + 2);
+    res.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c if c.is_control() => write!(res, "\\u{:04x}", c as u32).unwrap(),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+fn json_result(result: &Result<String, String>) -> String {
+    match result {
+        Ok(value) => format!("\"answer\":{},\"error\":null", json_string(value)),
+        Err(err) => format!("\"answer\":null,\"error\":{}", json_string(err)),
+    }
+}
+
+fn json_verdict(verdict: Option<&Verdict>) -> String {
+    match verdict {
+        Some(Verdict::Correct) => "\"verdict\":\"correct\",\"expected\":null".to_owned(),
+        Some(Verdict::Wrong { expected }) => {
+            format!(
+                "\"verdict\":\"wrong\",\"expected\":{}",
+                json_string(expected)
+            )
+        }
+        Some(Verdict::New) => "\"verdict\":\"new\",\"expected\":null".to_owned(),
+        None => "\"verdict\":null,\"expected\":null".to_owned(),
+    }
+}
+
+fn json_allocations(allocations: Option<Allocations>) -> String {
+    match allocations {
+        Some(allocations) => format!(
+            "{{\"count\":{},\"bytes\":{},\"peak_bytes\":{}}}",
+            allocations.count, allocations.bytes, allocations.peak
+        ),
+        None => "null".to_owned(),
+    }
+}
+
+fn verdict_name(verdict: Option<&Verdict>) -> &'static str {
+    match verdict {
+        Some(Verdict::Correct) => "correct",
+        Some(Verdict::Wrong { .. }) => "wrong",
+        Some(Verdict::New) => "new",
+        None => "",
+    }
+}
+
+impl Reporter for JsonReporter {
+    fn report_day(&mut self, report: &DayReport) {
+        let evaluation = &report.evaluation;
+        let mut record = format!(
+            "{{\"year\":{},\"day\":{},\"profile\":{},\"parse\":{{\"name\":{},\"error\":{},\"duration_ns\":{},\"allocations\":{}}},\"parts\":[",
+            report.year,
+            report.day_nr,
+            report
+                .profile
+                .as_deref()
+                .map_or_else(|| "null".to_owned(), json_string),
+            json_string(evaluation.parse.name),
+            match &evaluation.parse.result {
+                Ok(()) => "null".to_owned(),
+                Err(err) => json_string(&err.to_string()),
+            },
+            evaluation.parse.duration.as_nanos(),
+            json_allocations(evaluation.parse.allocations),
+        );
+        if evaluation.parse.result.is_ok() {
+            for (i, (name, result, duration, allocations)) in
+                shown_results(evaluation).iter().enumerate()
+            {
+                if i != 0 {
+                    record.push(',');
+                }
+                write!(
+                    record,
+                    "{{\"name\":{},{},{},\"duration_ns\":{},\"allocations\":{}}}",
+                    json_string(name),
+                    json_result(result),
+                    json_verdict(report.verdicts.get(i).and_then(Option::as_ref)),
+                    duration.as_nanos(),
+                    json_allocations(*allocations),
+                )
+                .unwrap();
+            }
+        }
+        write!(
+            record,
+            "],\"duration_ns\":{}",
+            evaluation.total_duration().as_nanos()
+        )
+        .unwrap();
+        if let Some(stats) = &report.stats {
+            write!(
+                record,
+                ",\"bench\":{{\"runs\":{},\"min_ns\":{},\"median_ns\":{},\"mean_ns\":{},\"stddev_ns\":{}}}",
+                stats.runs,
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.mean.as_nanos(),
+                stats.stddev.as_nanos(),
+            )
+            .unwrap();
+        }
+        record.push('}');
+        println!("{}", record);
+    }
+}
+
+struct CsvReporter;
+
+/// Quotes a CSV field if it contains a separator, quote or line break.
+fn csv_field(s: &str) -> String {
+    if s.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
+
+/// Formats the allocations of a phase as three fields, which are left empty if
+/// allocations are not counted.
+fn csv_allocations(allocations: Option<Allocations>) -> String {
+    match allocations {
+        Some(allocations) => format!(
+            ",{},{},{}",
+            allocations.count, allocations.bytes, allocations.peak
+        ),
+        None => ",,,".to_owned(),
+    }
+}
+
+impl Reporter for CsvReporter {
+    fn begin(&mut self) {
+        println!(
+            "year,day,profile,parse_name,parse_error,parse_ns,\
+            parse_allocs,parse_bytes,parse_peak_bytes,\
+            pt1_name,pt1_answer,pt1_error,pt1_verdict,pt1_ns,\
+            pt1_allocs,pt1_bytes,pt1_peak_bytes,\
+            pt2_name,pt2_answer,pt2_error,pt2_verdict,pt2_ns,\
+            pt2_allocs,pt2_bytes,pt2_peak_bytes,total_ns"
+        );
+    }
+
+    fn report_day(&mut self, report: &DayReport) {
+        let evaluation = &report.evaluation;
+        let mut record = format!(
+            "{},{},{},{},{},{}",
+            report.year,
+            report.day_nr,
+            csv_field(report.profile.as_deref().unwrap_or("")),
+            csv_field(evaluation.parse.name),
+            match &evaluation.parse.result {
+                Ok(()) => String::new(),
+                Err(err) => csv_field(&err.to_string()),
+            },
+            evaluation.parse.duration.as_nanos(),
+        );
+        record.push_str(&csv_allocations(evaluation.parse.allocations));
+        let mut parts = evaluation.parts.iter();
+        for index in 0..2 {
+            match parts.next() {
+                Some(part) => {
+                    let (answer, error) = match &part.result {
+                        Ok(value) => (csv_field(value), String::new()),
+                        Err(err) => (String::new(), csv_field(&err.to_string())),
+                    };
+                    write!(
+                        record,
+                        ",{},{},{},{},{}{}",
+                        csv_field(part.name),
+                        answer,
+                        error,
+                        verdict_name(report.verdicts.get(index).and_then(Option::as_ref)),
+                        part.duration.as_nanos(),
+                        csv_allocations(part.allocations)
+                    )
+                    .unwrap();
+                }
+                None => record.push_str(",,,,,,,,"),
+            }
+        }
+        write!(record, ",{}", evaluation.total_duration().as_nanos()).unwrap();
+        println!("{}", record);
+    }
+}
+
+/// Marks the start of the region of the README that holds the results table.
+pub const README_START_MARKER: &str = "<!-- results:start -->";
+/// Marks the end of the region of the README that holds the results table.
+pub const README_END_MARKER: &str = "<!-- results:end -->";
+
+struct MarkdownReporter {
+    year: u32,
+    redact: bool,
+    /// The README to write the table into, instead of printing it.
+    readme_file: Option<PathBuf>,
+    rows: Vec<String>,
+    /// The total durations of parsing, both parts, and every day.
+    totals: [Duration; 4],
+}
+
+/// Escapes text for use in a cell of a Markdown table, where line breaks are
+/// written as `<br>`.
+fn markdown_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', "<br>")
+}
+
+fn markdown_duration(duration: Duration) -> String {
+    format!("{} ms", format_duration(duration).trim_start())
+}
+
+/// Finds a marker that is on a line of its own, so that mentions of it in the
+/// text of the README are skipped.
+fn find_marker(contents: &str, marker: &str) -> Option<usize> {
+    contents
+        .match_indices(marker)
+        .map(|(index, _)| index)
+        .find(|&index| {
+            let rest = contents[index + marker.len()..]
+                .lines()
+                .next()
+                .unwrap_or("");
+            (index == 0 || contents[..index].ends_with('\n')) && rest.trim().is_empty()
+        })
+}
+
+/// Replaces the contents of the region between the markers, keeping the
+/// markers themselves. Returns `None` if the markers are missing.
+fn replace_marked_region(contents: &str, replacement: &str) -> Option<String> {
+    let start = find_marker(contents, README_START_MARKER)? + README_START_MARKER.len();
+    let end = start + find_marker(&contents[start..], README_END_MARKER)?;
+    Some(format!(
+        "{}\n{}{}",
+        &contents[..start],
+        replacement,
+        &contents[end..]
+    ))
+}
+
+impl Reporter for MarkdownReporter {
+    fn report_day(&mut self, report: &DayReport) {
+        let evaluation = &report.evaluation;
+        let mut label = day_label(report.year, self.year, report.day_nr);
+        if let Some(profile) = &report.profile {
+            write!(label, " [{}]", profile).unwrap();
+        }
+        let mut row = format!("| {} |", markdown_cell(&label));
+        for index in 0..2 {
+            let answer = match evaluation.parts.get(index).map(|part| &part.result) {
+                Some(Ok(_)) if self.redact => "*redacted*".to_owned(),
+                Some(Ok(value)) => markdown_cell(value),
+                Some(Err(_)) => "failed".to_owned(),
+                None if evaluation.parse.result.is_err() => "failed".to_owned(),
+                None => String::new(),
+            };
+            write!(row, " {} |", answer).unwrap();
+        }
+
+        let mut durations = [Duration::default(); 4];
+        durations[0] = evaluation.parse.duration;
+        for (index, part) in evaluation.parts.iter().enumerate() {
+            durations[1 + index] = part.duration;
+        }
+        durations[3] = evaluation.total_duration();
+        for (total, duration) in self.totals.iter_mut().zip(&durations) {
+            *total += *duration;
+            write!(row, " {} |", markdown_duration(*duration)).unwrap();
+        }
+        self.rows.push(row);
+    }
+
+    fn finish(&mut self, _summary: &Summary) -> Result<(), Error> {
+        let mut table = String::from(
+            "| Day | Part 1 | Part 2 | Parsing | Part 1 time | Part 2 time | Total time |\n\
+            |-----|--------|--------|--------:|------------:|------------:|-----------:|\n",
+        );
+        for row in &self.rows {
+            writeln!(table, "{}", row).unwrap();
+        }
+        write!(table, "| **Total** | | |").unwrap();
+        for total in &self.totals {
+            write!(table, " {} |", markdown_duration(*total)).unwrap();
+        }
+        table.push('\n');
+
+        match &self.readme_file {
+            Some(path) => {
+                let contents = fs::read_to_string(path)?;
+                let contents = replace_marked_region(&contents, &table).ok_or_else(|| {
+                    Error::InvalidInputDyn(format!(
+                        "{} has no region marked by {} and {}",
+                        path.display(),
+                        README_START_MARKER,
+                        README_END_MARKER
+                    ))
+                })?;
+                fs::write(path, contents)?;
+                println!("updated the results in {}", path.display());
+            }
+            None => print!("{}", table),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::traits::Phase;
+
+    fn day_report(day_nr: u32, profile: Option<&str>) -> DayReport {
+        DayReport {
+            year: 2020,
+            day_nr,
+            profile: profile.map(str::to_owned),
+            evaluation: Evaluation::new(Phase {
+                name: "parse",
+                result: Ok(()),
+                duration: Duration::default(),
+                allocations: None,
+            }),
+            stats: None,
+            verdicts: ArrayVec::new(),
+        }
+    }
+
+    #[test]
+    fn summary_days() {
+        let mut summary = Summary::default();
+        summary.add(&day_report(7, None));
+        summary.add(&day_report(7, Some("alice")));
+        summary.add(&day_report(8, None));
+        summary.add(&day_report(8, Some("alice")));
+        assert_eq!(summary.days, 2);
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(json_string("abc"), "\"abc\"");
+        assert_eq!(json_string("a\"b\\c\nd\u{1}"), "\"a\\\"b\\\\c\\nd\\u0001\"");
+        assert_eq!(csv_field("abc"), "abc");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("a\"b"), "\"a\"\"b\"");
+        assert_eq!(markdown_cell("a|b\nc"), "a\\|b<br>c");
+    }
+
+    #[test]
+    fn readme_regions() {
+        let readme = "# Title\n<!-- results:start -->\nold\n<!-- results:end -->\nrest\n";
+        assert_eq!(
+            replace_marked_region(readme, "| new |\n").unwrap(),
+            "# Title\n<!-- results:start -->\n| new |\n<!-- results:end -->\nrest\n"
+        );
+        assert_eq!(replace_marked_region("# Title\n", "| new |\n"), None);
+
+        let readme = "Between `<!-- results:start -->` and `<!-- results:end -->`:\n<!-- results:start -->\n<!-- results:end -->\n";
+        assert_eq!(
+            replace_marked_region(readme, "| new |\n").unwrap(),
+            "Between `<!-- results:start -->` and `<!-- results:end -->`:\n<!-- results:start -->\n| new |\n<!-- results:end -->\n"
+        );
+    }
+}
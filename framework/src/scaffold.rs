@@ -0,0 +1,190 @@
+use crate::{config::Config, error::Error, options::parse_day_selector};
+use std::{fs, path::PathBuf};
+
+/// Parses a module name such as `day07` into its day number.
+pub fn parse_day_nr(module_name: &str) -> Option<u32> {
+    module_name
+        .strip_prefix("day")
+        .filter(|nr| nr.len() == 2)
+        .and_then(|nr| nr.parse().ok())
+        .filter(|nr| (1..=25).contains(nr))
+}
+
+/// Returns the initial source of a day module, with stubs that report that
+/// they are not implemented yet.
+fn module_template(day_nr: u32) -> String {
+    format!(
+        r#"use crate::prelude::*;
+
+day!({}, parse => pt1, pt2);
+
+pub fn pt1(_input: &[&str]) -> Result<usize> {{
+    Err(Error::NotImplemented)
+}}
+
+pub fn pt2(_input: &[&str]) -> Result<usize> {{
+    Err(Error::NotImplemented)
+}}
+
+pub fn parse(input: &str) -> Result<Vec<&str>> {{
+    Ok(input.lines().collect())
+}}
+
+standard_tests!(
+    parse []
+    pt1 []
+    pt2 []
+);
+"#,
+        day_nr
+    )
+}
+
+/// Adds a module to the `main!` invocation, either to the days listed directly,
+/// or to the group of a year, which is added if it does not exist yet. Days are
+/// kept sorted. Returns `None` if the invocation cannot be found, or already
+/// contains the module.
+fn register_module(main_rs: &str, group: Option<u32>, module_name: &str) -> Option<String> {
+    let mut lines = main_rs.lines().map(str::to_owned).collect::<Vec<_>>();
+    let start = 1 + lines
+        .iter()
+        .position(|line| line.starts_with("framework::main! {"))?;
+    let end = start + lines[start..].iter().position(|line| line == "}")?;
+    let (first, last, indent) = match group {
+        None => {
+            let last = lines[start..end]
+                .iter()
+                .position(|line| line.ends_with('{'))
+                .map_or(end, |offset| start + offset);
+            (start, last, "    ")
+        }
+        Some(year) => {
+            let header = format!("{} {{", year);
+            match lines[start..end]
+                .iter()
+                .position(|line| line.trim() == header)
+            {
+                Some(offset) => {
+                    let first = start + offset + 1;
+                    let last = first
+                        + lines[first..end]
+                            .iter()
+                            .position(|line| line.trim() == "}")?;
+                    (first, last, "        ")
+                }
+                None => {
+                    lines.insert(end, "    }".to_owned());
+                    lines.insert(end, format!("    {}", header));
+                    (end + 1, end + 1, "        ")
+                }
+            }
+        }
+    };
+    let modules = &lines[first..last];
+    if modules.iter().any(|line| line.trim() == module_name) {
+        return None;
+    }
+    let position = first
+        + modules
+            .iter()
+            .take_while(|line| line.trim() < module_name)
+            .count();
+    lines.insert(position, format!("{}{}", indent, module_name));
+
+    let mut res = lines.join("\n");
+    if main_rs.ends_with('\n') {
+        res.push('\n');
+    }
+    Some(res)
+}
+
+/// Creates the module of a new day from a template, and registers it in
+/// `main.rs`. Days of the default year are listed directly, unless that year
+/// has a group of its own. Returns the path of the module.
+pub fn new_day(config: &Config, selector: &str) -> Result<PathBuf, Error> {
+    let invalid = || {
+        Error::InvalidArgument(format!(
+            "{} is not a day, such as day07 or 2019:day05",
+            selector
+        ))
+    };
+    let (year, module_name) = parse_day_selector(selector, config.year).ok_or_else(invalid)?;
+    let day_nr = parse_day_nr(module_name).ok_or_else(invalid)?;
+
+    let main_path = config.source_dir.join("main.rs");
+    let main_rs = fs::read_to_string(&main_path)?;
+    let has_group = main_rs
+        .lines()
+        .any(|line| line.trim() == format!("{} {{", year));
+    let (group, module_dir) = if year != config.year || has_group {
+        (Some(year), config.source_dir.join(format!("year{}", year)))
+    } else {
+        (None, config.source_dir.clone())
+    };
+    let module_path = module_dir.join(format!("{}.rs", module_name));
+    if module_path.exists() {
+        return Err(Error::InvalidArgument(format!(
+            "{} already exists",
+            module_path.display()
+        )));
+    }
+    let main_rs = register_module(&main_rs, group, module_name).ok_or_else(|| {
+        Error::InvalidInputDyn(format!(
+            "cannot register {} in {}",
+            selector,
+            main_path.display()
+        ))
+    })?;
+
+    fs::create_dir_all(module_dir)?;
+    fs::write(&module_path, module_template(day_nr))?;
+    fs::write(main_path, main_rs)?;
+    Ok(module_path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn day_nrs() {
+        assert_eq!(parse_day_nr("day07"), Some(7));
+        assert_eq!(parse_day_nr("day25"), Some(25));
+        assert_eq!(parse_day_nr("day7"), None);
+        assert_eq!(parse_day_nr("day26"), None);
+        assert_eq!(parse_day_nr("07"), None);
+    }
+
+    #[test]
+    fn registration() {
+        let main_rs = "mod prelude;\n\nframework::main! {\n    day01\n    day03\n}\n";
+        assert_eq!(
+            register_module(main_rs, None, "day02").unwrap(),
+            "mod prelude;\n\nframework::main! {\n    day01\n    day02\n    day03\n}\n"
+        );
+        assert_eq!(
+            register_module(main_rs, None, "day04").unwrap(),
+            "mod prelude;\n\nframework::main! {\n    day01\n    day03\n    day04\n}\n"
+        );
+        assert_eq!(register_module(main_rs, None, "day03"), None);
+        assert_eq!(register_module("fn main() {}", None, "day03"), None);
+    }
+
+    #[test]
+    fn registration_by_year() {
+        let main_rs = "framework::main! {\n    day01\n    2019 {\n        day05\n    }\n}\n";
+        assert_eq!(
+            register_module(main_rs, None, "day02").unwrap(),
+            "framework::main! {\n    day01\n    day02\n    2019 {\n        day05\n    }\n}\n"
+        );
+        assert_eq!(
+            register_module(main_rs, Some(2019), "day04").unwrap(),
+            "framework::main! {\n    day01\n    2019 {\n        day04\n        day05\n    }\n}\n"
+        );
+        assert_eq!(
+            register_module(main_rs, Some(2018), "day01").unwrap(),
+            "framework::main! {\n    day01\n    2019 {\n        day05\n    }\n    2018 {\n        day01\n    }\n}\n"
+        );
+        assert_eq!(register_module(main_rs, Some(2019), "day05"), None);
+    }
+}
@@ -0,0 +1,411 @@
+use crate::{
+    answers::{escape, unescape, Answers},
+    config::Config,
+    error::Error,
+    http::{self, RequestThrottle, RetryPolicy},
+    input::{self, SessionKey},
+    options::Options,
+    report::day_label,
+    traits::Day,
+};
+use std::{fmt, fs, io, path::PathBuf};
+
+/// What the server said about a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    /// An answer was submitted too recently, and another may only be submitted
+    /// after the given time, such as `1m 20s`.
+    Wait(String),
+    /// The part was already solved, or its previous part is not solved yet.
+    WrongLevel,
+}
+
+impl Outcome {
+    fn name(&self) -> &'static str {
+        match self {
+            Outcome::Correct => "correct",
+            Outcome::TooHigh => "too_high",
+            Outcome::TooLow => "too_low",
+            Outcome::Wrong => "wrong",
+            Outcome::Wait(_) => "wait",
+            Outcome::WrongLevel => "wrong_level",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Outcome> {
+        Some(match name {
+            "correct" => Outcome::Correct,
+            "too_high" => Outcome::TooHigh,
+            "too_low" => Outcome::TooLow,
+            "wrong" => Outcome::Wrong,
+            "wait" => Outcome::Wait(String::new()),
+            "wrong_level" => Outcome::WrongLevel,
+            _ => return None,
+        })
+    }
+
+    /// Whether this outcome says anything about the answer itself, rather
+    /// than about the circumstances under which it was submitted.
+    fn is_verdict(&self) -> bool {
+        matches!(
+            self,
+            Outcome::Correct | Outcome::TooHigh | Outcome::TooLow | Outcome::Wrong
+        )
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Correct => f.write_str("correct"),
+            Outcome::TooHigh => f.write_str("too high"),
+            Outcome::TooLow => f.write_str("too low"),
+            Outcome::Wrong => f.write_str("wrong"),
+            Outcome::Wait(time) if time.is_empty() => f.write_str("submitted too recently"),
+            Outcome::Wait(time) => write!(f, "submitted too recently, wait {}", time),
+            Outcome::WrongLevel => f.write_str("not the current part of this day"),
+        }
+    }
+}
+
+/// Parses the page the server responds with after submitting an answer.
+pub fn parse_response(html: &str) -> Result<Outcome, Error> {
+    let text = match (html.find("<article>"), html.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &html[start..end],
+        _ => html,
+    };
+    Ok(if text.contains("That's the right answer") {
+        Outcome::Correct
+    } else if text.contains("You gave an answer too recently") {
+        let time = text
+            .split("You have ")
+            .nth(1)
+            .and_then(|rest| rest.split(" left to wait").next())
+            .unwrap_or("")
+            .to_owned();
+        Outcome::Wait(time)
+    } else if text.contains("You don't seem to be solving the right level") {
+        Outcome::WrongLevel
+    } else if text.contains("That's not the right answer") {
+        if text.contains("your answer is too high") {
+            Outcome::TooHigh
+        } else if text.contains("your answer is too low") {
+            Outcome::TooLow
+        } else {
+            Outcome::Wrong
+        }
+    } else {
+        return Err(Error::InvalidInput("unrecognized response to submission"));
+    })
+}
+
+/// A single submitted answer, as stored in the submission log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    pub year: u32,
+    pub day_nr: u32,
+    pub part: usize,
+    pub outcome: Outcome,
+    pub answer: String,
+}
+
+/// Every answer that was ever submitted, stored as lines in the format
+/// `2020 day07 pt1 too_high = answer`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SubmissionLog(Vec<Attempt>);
+
+pub fn get_submission_log_path(config: &Config) -> PathBuf {
+    config.answers_dir.join("submissions.txt")
+}
+
+impl SubmissionLog {
+    pub fn load(config: &Config) -> Result<SubmissionLog, Error> {
+        match fs::read_to_string(get_submission_log_path(config)) {
+            Ok(contents) => SubmissionLog::parse(&contents),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(SubmissionLog::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Adds an attempt to the log, and appends it to the file.
+    pub fn append(&mut self, config: &Config, attempt: Attempt) -> Result<(), Error> {
+        use std::io::Write;
+        let path = get_submission_log_path(config);
+        let mut dir_path = path.clone();
+        dir_path.pop();
+        fs::create_dir_all(dir_path)?;
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        writeln!(file, "{}", format_attempt(&attempt))?;
+        self.0.push(attempt);
+        Ok(())
+    }
+
+    pub fn parse(contents: &str) -> Result<SubmissionLog, Error> {
+        let mut log = SubmissionLog::default();
+        for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+            let invalid_line =
+                || Error::InvalidInputDyn(format!("invalid submission line: {}", line));
+            let mut split = line.splitn(2, " = ");
+            let mut fields = split.next().ok_or_else(invalid_line)?.split(' ');
+            let year = fields.next().and_then(|year| year.parse().ok());
+            let day_nr = fields
+                .next()
+                .and_then(|day| day.strip_prefix("day"))
+                .and_then(|nr| nr.parse().ok());
+            let part = fields
+                .next()
+                .and_then(|part| part.strip_prefix("pt"))
+                .and_then(|nr| nr.parse().ok());
+            let outcome = fields.next().and_then(Outcome::from_name);
+            let answer = split.next().and_then(unescape);
+            match (year, day_nr, part, outcome, answer, fields.next()) {
+                (Some(year), Some(day_nr), Some(part), Some(outcome), Some(answer), None) => {
+                    log.0.push(Attempt {
+                        year,
+                        day_nr,
+                        part,
+                        outcome,
+                        answer,
+                    })
+                }
+                _ => return Err(invalid_line()),
+            }
+        }
+        Ok(log)
+    }
+
+    /// Returns what is already known about an answer from earlier attempts.
+    /// Besides answers which were submitted before, numeric answers are also
+    /// known to be wrong when they are outside of the bounds given by earlier
+    /// answers that were too high or too low, or when another answer was
+    /// correct.
+    pub fn known_outcome(
+        &self,
+        year: u32,
+        day_nr: u32,
+        part: usize,
+        answer: &str,
+    ) -> Option<Outcome> {
+        let attempts = self.0.iter().filter(|attempt| {
+            attempt.year == year
+                && attempt.day_nr == day_nr
+                && attempt.part == part
+                && attempt.outcome.is_verdict()
+        });
+        let number = answer.parse::<i64>().ok();
+        let mut known = None;
+        for attempt in attempts {
+            if attempt.answer == answer {
+                return Some(attempt.outcome.clone());
+            }
+            let attempt_number = attempt.answer.parse::<i64>().ok();
+            known = match (&attempt.outcome, number, attempt_number) {
+                (Outcome::Correct, _, _) => Some(Outcome::Wrong),
+                (Outcome::TooHigh, Some(number), Some(bound)) if number > bound => {
+                    Some(Outcome::TooHigh)
+                }
+                (Outcome::TooLow, Some(number), Some(bound)) if number < bound => {
+                    Some(Outcome::TooLow)
+                }
+                _ => known,
+            };
+        }
+        known
+    }
+}
+
+fn format_attempt(attempt: &Attempt) -> String {
+    format!(
+        "{} day{:0>2} pt{} {} = {}",
+        attempt.year,
+        attempt.day_nr,
+        attempt.part,
+        attempt.outcome.name(),
+        escape(&attempt.answer)
+    )
+}
+
+/// Submits an answer, unless the submission log already shows whether it is
+/// correct. Every attempt is logged, and correct answers are also recorded
+/// as the known-good answers of the day.
+pub fn submit(
+    config: &Config,
+    throttle: &mut RequestThrottle,
+    session_key: &mut SessionKey,
+    year: u32,
+    day_nr: u32,
+    part: usize,
+    answer: &str,
+) -> Result<Outcome, Error> {
+    let mut log = SubmissionLog::load(config)?;
+    if let Some(outcome) = log.known_outcome(year, day_nr, part, answer) {
+        eprintln!(
+            "not submitting, the submission log already shows that {} is {}",
+            answer, outcome
+        );
+        return Ok(outcome);
+    }
+
+    let url = format!("{}/{}/day/{}/answer", config.base_url, year, day_nr);
+    // Retrying could submit the same answer twice, which would count as an
+    // extra wrong attempt, so failures are reported immediately instead.
+    let retry = RetryPolicy {
+        attempts: 1,
+        ..RetryPolicy::default()
+    };
+    let level = part.to_string();
+    let response = http::post_form(
+        throttle,
+        &retry,
+        &url,
+        input::get_session_key(config, session_key)?,
+        &[("level", &level), ("answer", answer)],
+    )?;
+    let outcome = parse_response(&response)?;
+
+    log.append(
+        config,
+        Attempt {
+            year,
+            day_nr,
+            part,
+            outcome: outcome.clone(),
+            answer: answer.to_owned(),
+        },
+    )?;
+    if outcome == Outcome::Correct {
+        let mut answers = Answers::load(config, None, year, day_nr)?;
+        answers.set(part - 1, answer.to_owned());
+        answers.save(config, None, year, day_nr)?;
+    }
+    Ok(outcome)
+}
+
+/// Evaluates a day against the default input, and submits the answer of one
+/// of its parts. Nothing is submitted if parsing or the part fails.
+pub fn run(
+    config: &Config,
+    throttle: &mut RequestThrottle,
+    session_key: &mut SessionKey,
+    options: &Options,
+    year: u32,
+    day: &dyn Day,
+    part: usize,
+) -> Result<(), Error> {
+    let input = input::load_input(config, throttle, session_key, options, year, day.nr(), None)?;
+    let evaluation = day.evaluate(input);
+    evaluation.parse.result?;
+    let answer = match evaluation.parts.into_iter().nth(part - 1) {
+        Some(phase) => phase.result?,
+        None => return Err(Error::NotImplemented),
+    };
+    let outcome = submit(config, throttle, session_key, year, day.nr(), part, &answer)?;
+    println!(
+        "{} pt{}: {} is {}",
+        day_label(year, config.year, day.nr()),
+        part,
+        answer,
+        outcome
+    );
+    match outcome {
+        Outcome::Correct => Ok(()),
+        outcome => Err(Error::AnswerRejected(outcome.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::http::test_server::serve_with_config;
+
+    fn page(message: &str) -> String {
+        format!(
+            "<html><body><main>\n<article><p>{}</p></article>\n</main></body></html>",
+            message
+        )
+    }
+
+    #[test]
+    fn responses() {
+        let correct = page("That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer to saving your vacation.");
+        let too_high = page("That's not the right answer; your answer is too high.  If you're stuck, make sure you're using the full input data; there are also some general tips on the <a href=\"/2020/about\">about page</a>. Please wait one minute before trying again. (You guessed <span style=\"white-space:nowrap;\"><code>5000</code>.)</span>");
+        let too_low = page("That's not the right answer; your answer is too low.  Please wait one minute before trying again.");
+        let wrong = page("That's not the right answer.  If you're stuck, make sure you're using the full input data.");
+        let wait = page("You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 20s left to wait. <a href=\"/2020/day/7\">[Return to Day 7]</a>");
+        let level = page("You don't seem to be solving the right level.  Did you already complete it? <a href=\"/2020/day/7\">[Return to Day 7]</a>");
+
+        assert_eq!(parse_response(&correct).unwrap(), Outcome::Correct);
+        assert_eq!(parse_response(&too_high).unwrap(), Outcome::TooHigh);
+        assert_eq!(parse_response(&too_low).unwrap(), Outcome::TooLow);
+        assert_eq!(parse_response(&wrong).unwrap(), Outcome::Wrong);
+        assert_eq!(
+            parse_response(&wait).unwrap(),
+            Outcome::Wait("1m 20s".to_owned())
+        );
+        assert_eq!(parse_response(&level).unwrap(), Outcome::WrongLevel);
+        assert!(parse_response("<html></html>").is_err());
+    }
+
+    #[test]
+    fn known_outcomes() {
+        let log = SubmissionLog::parse(
+            "2020 day07 pt1 too_high = 500\n2020 day07 pt1 too_low = 100\n2020 day07 pt1 wait = 300\n2020 day08 pt2 correct = a\\nb\n",
+        )
+        .unwrap();
+        assert_eq!(log.known_outcome(2020, 7, 1, "500"), Some(Outcome::TooHigh));
+        assert_eq!(log.known_outcome(2020, 7, 1, "600"), Some(Outcome::TooHigh));
+        assert_eq!(log.known_outcome(2020, 7, 1, "50"), Some(Outcome::TooLow));
+        assert_eq!(log.known_outcome(2020, 7, 1, "300"), None);
+        assert_eq!(log.known_outcome(2020, 7, 2, "600"), None);
+        assert_eq!(log.known_outcome(2019, 7, 1, "600"), None);
+        assert_eq!(
+            log.known_outcome(2020, 8, 2, "a\nb"),
+            Some(Outcome::Correct)
+        );
+        assert_eq!(log.known_outcome(2020, 8, 2, "c"), Some(Outcome::Wrong));
+        assert!(SubmissionLog::parse("2020 day07 pt1 maybe = 5").is_err());
+    }
+
+    #[test]
+    fn submissions() {
+        let (server, _dir, config, mut throttle, mut session_key) = serve_with_config(
+            "submit",
+            vec![
+                (
+                    200,
+                    "<article><p>That's not the right answer; your answer is too high.</p></article>",
+                ),
+                (200, "<article><p>That's the right answer!</p></article>"),
+            ],
+        );
+        let (throttle, session_key) = (&mut throttle, &mut session_key);
+        let mut submit_answer =
+            |answer| submit(&config, throttle, session_key, 2020, 7, 2, answer).unwrap();
+
+        assert_eq!(submit_answer("1000"), Outcome::TooHigh);
+        // Known to be too high, so it is not submitted again.
+        assert_eq!(submit_answer("1200"), Outcome::TooHigh);
+        assert_eq!(submit_answer("800"), Outcome::Correct);
+        assert_eq!(submit_answer("800"), Outcome::Correct);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].starts_with("POST /2020/day/7/answer HTTP/1.1\r\n"));
+        assert!(requests[0].ends_with("\r\n\r\nlevel=2&answer=1000"));
+        assert_eq!(
+            fs::read_to_string(get_submission_log_path(&config)).unwrap(),
+            "2020 day07 pt2 too_high = 1000\n2020 day07 pt2 correct = 800\n"
+        );
+        assert_eq!(
+            Answers::load(&config, None, 2020, 7).unwrap().get(1),
+            Some("800")
+        );
+    }
+}
@@ -1,3 +1,33 @@
+use crate::{config::Config, puzzle};
+
+/// Returns an example of a day by its index, as extracted from the puzzle
+/// description by running `examples dayXX`. The configuration is loaded the
+/// same way as by the runner, from the `aoc.toml` found by searching the
+/// current directory and its parents, so tests using it must run inside the
+/// crate, as `cargo test` does.
+pub fn example(year: u32, day_nr: u32, index: usize) -> String {
+    let config = Config::load().unwrap();
+    puzzle::load_example(&config, year, day_nr, index).unwrap_or_else(|err| {
+        panic!(
+            "example {} of {}:day{:0>2} is not available ({}), run `examples {}:day{:0>2}` first",
+            index, year, day_nr, err, year, day_nr
+        )
+    })
+}
+
+/// Returns the highlighted answers in one part of the puzzle description of a
+/// day, in the order they appear in. Like [`example`], this depends on the
+/// current directory.
+pub fn highlights(year: u32, day_nr: u32, part: usize) -> Vec<String> {
+    let config = Config::load().unwrap();
+    puzzle::load_highlights(&config, year, day_nr, part).unwrap_or_else(|err| {
+        panic!(
+            "highlights of {}:day{:0>2} are not available ({}), run `examples {}:day{:0>2}` first",
+            year, day_nr, err, year, day_nr
+        )
+    })
+}
+
 #[macro_export]
 macro_rules! standard_tests {
     (
@@ -0,0 +1,241 @@
+use crate::{
+    cancel,
+    error::Error,
+    traits::{Day, Evaluation, Phase, PhaseResult},
+};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+fn failed_phase(index: usize, name: &'static str, error: Error, duration: Duration) -> PhaseResult {
+    if index == 0 {
+        PhaseResult::Parse(Phase {
+            name,
+            result: Err(error),
+            duration,
+            allocations: None,
+        })
+    } else {
+        PhaseResult::Part(Phase {
+            name,
+            result: Err(error),
+            duration,
+            allocations: None,
+        })
+    }
+}
+
+/// Evaluates a day on a worker thread, allowing each phase to take at most
+/// `timeout`. A phase that exceeds it is reported as timed out, and is asked to
+/// cancel. If it does not stop within another `timeout`, the worker thread is
+/// abandoned, and the remaining phases are reported as cancelled. The worker
+/// does not start any of them, but the phase it is running only stops early if
+/// it polls `cancel::check`, and otherwise runs to completion.
+pub fn evaluate(day: &'static dyn Day, input: String, timeout: Duration) -> Evaluation {
+    let (sender, receiver) = mpsc::channel();
+    let first_token = Arc::new(AtomicBool::new(false));
+    let mut token = first_token.clone();
+    let abandoned = Arc::new(AtomicBool::new(false));
+    let worker_abandoned = abandoned.clone();
+    thread::spawn(move || {
+        cancel::set_token(Some(first_token));
+        day.evaluate_phases(input, &mut |phase| {
+            let next_token = Arc::new(AtomicBool::new(false));
+            cancel::set_token(Some(next_token.clone()));
+            sender.send((phase, next_token)).is_ok() && !worker_abandoned.load(Ordering::SeqCst)
+        });
+    });
+
+    let names = day.phase_names();
+    let mut evaluation = None;
+    let mut index = 0;
+    let mut timed_out = false;
+    loop {
+        match receiver.recv_timeout(timeout) {
+            Ok((phase, next_token)) => {
+                // The result of a phase that already timed out is discarded.
+                if !timed_out {
+                    phase.add_to(&mut evaluation);
+                }
+                token = next_token;
+                timed_out = false;
+                index += 1;
+            }
+            Err(RecvTimeoutError::Timeout) if !timed_out => {
+                token.store(true, Ordering::Relaxed);
+                failed_phase(index, names[index], Error::TimedOut, timeout).add_to(&mut evaluation);
+                timed_out = true;
+                // Parts cannot run without the parsed input.
+                if index == 0 {
+                    abandoned.store(true, Ordering::SeqCst);
+                    break;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                // A phase that finished before the worker saw this flag has
+                // already started the next one, which is cancelled instead.
+                abandoned.store(true, Ordering::SeqCst);
+                for (_, next_token) in receiver.try_iter() {
+                    next_token.store(true, Ordering::Relaxed);
+                }
+                for (index, &name) in names.iter().enumerate().skip(index + 1) {
+                    failed_phase(index, name, Error::Cancelled, Duration::default())
+                        .add_to(&mut evaluation);
+                }
+                break;
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    evaluation.expect("parser was not evaluated")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::traits::run_phase;
+    use std::sync::atomic::AtomicUsize;
+
+    const TIMEOUT: Duration = Duration::from_millis(50);
+
+    /// A day whose parser and parts are given as functions.
+    struct TestDay {
+        parse: fn() -> Result<(), Error>,
+        parts: [fn() -> Result<String, Error>; 2],
+    }
+
+    impl Day for TestDay {
+        fn nr(&self) -> u32 {
+            1
+        }
+
+        fn phase_names(&self) -> &'static [&'static str] {
+            &["parse", "pt1", "pt2"]
+        }
+
+        fn variant_names(&self) -> [&'static [&'static str]; 2] {
+            [&["pt1"], &["pt2"]]
+        }
+
+        fn evaluate_phases(&self, _input: String, report: &mut dyn FnMut(PhaseResult) -> bool) {
+            let (result, duration, allocations) = run_phase(self.parse);
+            if !report(PhaseResult::Parse(Phase {
+                name: "parse",
+                result,
+                duration,
+                allocations,
+            })) {
+                return;
+            }
+            for (part, name) in self.parts.iter().zip(&["pt1", "pt2"]) {
+                let (result, duration, allocations) = run_phase(part);
+                if !report(PhaseResult::Part(Phase {
+                    name,
+                    result,
+                    duration,
+                    allocations,
+                })) {
+                    return;
+                }
+            }
+        }
+
+        fn evaluate_variants(&self, input: String, report: &mut dyn FnMut(PhaseResult) -> bool) {
+            self.evaluate_phases(input, report);
+        }
+    }
+
+    fn errors(evaluation: &Evaluation) -> Vec<Option<String>> {
+        evaluation
+            .parts
+            .iter()
+            .map(|part| part.result.as_ref().err().map(Error::to_string))
+            .collect()
+    }
+
+    static SLEEPING_PT2_RUNS: AtomicUsize = AtomicUsize::new(0);
+    static SLEEPING: TestDay = TestDay {
+        parse: || Ok(()),
+        parts: [
+            || {
+                thread::sleep(TIMEOUT * 6);
+                Ok("1".to_owned())
+            },
+            || {
+                SLEEPING_PT2_RUNS.fetch_add(1, Ordering::Relaxed);
+                Ok("2".to_owned())
+            },
+        ],
+    };
+
+    #[test]
+    fn abandoned_parts() {
+        let evaluation = evaluate(&SLEEPING, String::new(), TIMEOUT);
+        assert_eq!(
+            errors(&evaluation),
+            vec![Some("timed out".to_owned()), Some("cancelled".to_owned())]
+        );
+        // Once the abandoned first part finishes, the second part is not
+        // started, even though it does not check for cancellation.
+        thread::sleep(TIMEOUT * 8);
+        assert_eq!(SLEEPING_PT2_RUNS.load(Ordering::Relaxed), 0);
+    }
+
+    static LOOPING: TestDay = TestDay {
+        parse: || Ok(()),
+        parts: [
+            || loop {
+                cancel::check()?;
+            },
+            || Ok("2".to_owned()),
+        ],
+    };
+
+    #[test]
+    fn cancelled_parts() {
+        let evaluation = evaluate(&LOOPING, String::new(), TIMEOUT);
+        assert_eq!(
+            errors(&evaluation),
+            vec![Some("timed out".to_owned()), None]
+        );
+        assert_eq!(evaluation.parts[1].result.as_ref().unwrap(), "2");
+    }
+
+    static SLOW_PARSE_PART_RUNS: AtomicUsize = AtomicUsize::new(0);
+    static SLOW_PARSE: TestDay = TestDay {
+        parse: || {
+            thread::sleep(TIMEOUT * 2);
+            Ok(())
+        },
+        parts: [
+            || {
+                SLOW_PARSE_PART_RUNS.fetch_add(1, Ordering::Relaxed);
+                Ok("1".to_owned())
+            },
+            || {
+                SLOW_PARSE_PART_RUNS.fetch_add(1, Ordering::Relaxed);
+                Ok("2".to_owned())
+            },
+        ],
+    };
+
+    #[test]
+    fn parse_timeout() {
+        let evaluation = evaluate(&SLOW_PARSE, String::new(), TIMEOUT);
+        assert_eq!(
+            evaluation.parse.result.unwrap_err().to_string(),
+            "timed out"
+        );
+        assert!(evaluation.parts.is_empty());
+        // The parts are not run once the parser finishes.
+        thread::sleep(TIMEOUT * 4);
+        assert_eq!(SLOW_PARSE_PART_RUNS.load(Ordering::Relaxed), 0);
+    }
+}
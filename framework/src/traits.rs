@@ -1,5 +1,6 @@
-use crate::error::Error;
+use crate::{error::Error, memory::Allocations};
 use arrayvec::ArrayVec;
+use std::time::{Duration, Instant};
 
 pub trait IntoResult {
     type Item;
@@ -43,9 +44,120 @@ impl IntoError for Error {
     }
 }
 
-pub trait Day {
+/// The outcome of a single phase (parsing, or one of the parts) of a day.
+#[derive(Debug)]
+pub struct Phase<T> {
+    pub name: &'static str,
+    pub result: Result<T, Error>,
+    pub duration: Duration,
+    /// The allocations made by the phase, if they are counted.
+    pub allocations: Option<Allocations>,
+}
+
+/// The outcome of evaluating a day. If parsing fails, no parts are evaluated.
+#[derive(Debug)]
+pub struct Evaluation {
+    pub parse: Phase<()>,
+    pub parts: ArrayVec<[Phase<String>; 2]>,
+}
+
+impl Evaluation {
+    pub fn new(parse: Phase<()>) -> Evaluation {
+        Evaluation {
+            parse,
+            parts: ArrayVec::new(),
+        }
+    }
+
+    /// Replaces the durations and allocations of every phase with those of
+    /// the same phase in another evaluation of the same day.
+    pub fn take_timings(&mut self, other: &Evaluation) {
+        self.parse.duration = other.parse.duration;
+        self.parse.allocations = other.parse.allocations;
+        for (part, other_part) in self.parts.iter_mut().zip(&other.parts) {
+            part.duration = other_part.duration;
+            part.allocations = other_part.allocations;
+        }
+    }
+
+    pub fn total_duration(&self) -> Duration {
+        self.parse.duration
+            + self
+                .parts
+                .iter()
+                .map(|part| part.duration)
+                .sum::<Duration>()
+    }
+}
+
+/// A finished phase, as reported while a day is being evaluated.
+#[derive(Debug)]
+pub enum PhaseResult {
+    Parse(Phase<()>),
+    Part(Phase<String>),
+}
+
+impl PhaseResult {
+    /// Adds this phase to an evaluation in progress, which is started by the
+    /// parse phase.
+    pub fn add_to(self, evaluation: &mut Option<Evaluation>) {
+        match self {
+            PhaseResult::Parse(parse) => *evaluation = Some(Evaluation::new(parse)),
+            PhaseResult::Part(part) => evaluation
+                .as_mut()
+                .expect("part finished before parsing")
+                .parts
+                .push(part),
+        }
+    }
+}
+
+/// Runs a function, and measures how long it took.
+pub fn timed<T, F: FnOnce() -> T>(f: F) -> (T, Duration) {
+    let start_time = Instant::now();
+    let value = f();
+    (value, Instant::now() - start_time)
+}
+
+/// Runs a single phase of a day, measuring how long it took and, if enabled,
+/// what it allocated. A panic inside of the phase is reported as an error,
+/// rather than aborting the runner.
+pub fn run_phase<T, F: FnOnce() -> Result<T, Error>>(
+    f: F,
+) -> (Result<T, Error>, Duration, Option<Allocations>) {
+    let ((result, duration), allocations) =
+        crate::memory::measure(|| timed(|| crate::panic::catch(f)));
+    (result, duration, allocations)
+}
+
+pub trait Day: Sync {
     fn nr(&self) -> u32;
-    fn evaluate(&self, input: String) -> ArrayVec<[(&'static str, Result<String, Error>); 2]>;
+
+    /// The name of the parser, followed by the names of the parts.
+    fn phase_names(&self) -> &'static [&'static str];
+
+    /// Evaluates the parser and the parts, passing each phase to `report` as
+    /// soon as it finishes. The remaining phases are skipped once `report`
+    /// returns `false`.
+    fn evaluate_phases(&self, input: String, report: &mut dyn FnMut(PhaseResult) -> bool);
+
+    /// The names of the implementations of each part. The first one is the
+    /// one used by `evaluate_phases`, the others are alternatives.
+    fn variant_names(&self) -> [&'static [&'static str]; 2];
+
+    /// Evaluates the parser, followed by every implementation of every part,
+    /// in the order of `variant_names`. Parts are named after their
+    /// implementation.
+    fn evaluate_variants(&self, input: String, report: &mut dyn FnMut(PhaseResult) -> bool);
+
+    fn evaluate(&self, input: String) -> Evaluation {
+        let mut evaluation = None;
+        self.evaluate_phases(input, &mut |phase| {
+            phase.add_to(&mut evaluation);
+            true
+        });
+        evaluation.expect("parser was not evaluated")
+    }
 }
 
 pub trait ResultWhereValueIsErrorExt {
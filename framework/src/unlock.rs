@@ -0,0 +1,89 @@
+use std::{
+    io::{self, Write},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Puzzles unlock at midnight US Eastern time (UTC-5), which is 05:00 UTC.
+const UNLOCK_HOUR_UTC: u64 = 5;
+/// Extra time to wait after the unlock, in case the local clock runs ahead of
+/// the server's clock.
+const UNLOCK_MARGIN: Duration = Duration::from_secs(1);
+
+/// Returns the number of days from 1970-01-01 to the given date.
+fn days_since_epoch(year: u64, month: u64, day: u64) -> u64 {
+    // Count years from March, so the leap day is at the end of the year.
+    let (year, month) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Returns the moment at which the puzzle of a day unlocks.
+pub fn unlock_time(year: u32, day_nr: u32) -> SystemTime {
+    let days = days_since_epoch(year as u64, 12, day_nr as u64);
+    UNIX_EPOCH + Duration::from_secs(days * 86_400 + UNLOCK_HOUR_UTC * 3_600)
+}
+
+fn format_countdown(remaining: Duration) -> String {
+    let seconds = remaining.as_secs() + (remaining.subsec_nanos() > 0) as u64;
+    format!(
+        "{:0>2}:{:0>2}:{:0>2}",
+        seconds / 3_600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Blocks until the puzzle of a day has unlocked, showing a countdown on
+/// stderr in the meantime.
+pub fn wait_for_unlock(year: u32, day_nr: u32) {
+    let unlock_time = unlock_time(year, day_nr) + UNLOCK_MARGIN;
+    let mut waited = false;
+    while let Ok(remaining) = unlock_time.duration_since(SystemTime::now()) {
+        eprint!(
+            "\rday{:0>2} unlocks in {}",
+            day_nr,
+            format_countdown(remaining)
+        );
+        let _ = io::stderr().flush();
+        waited = true;
+        thread::sleep(remaining.min(Duration::from_millis(remaining.subsec_millis() as u64 + 1)));
+    }
+    if waited {
+        eprintln!("\rday{:0>2} unlocked, fetching input", day_nr);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unlock_times() {
+        let secs = |year, day_nr| {
+            unlock_time(year, day_nr)
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+        };
+        // 2020-12-01T05:00:00Z
+        assert_eq!(secs(2020, 1), 1_606_798_800);
+        // 2020-12-25T05:00:00Z
+        assert_eq!(secs(2020, 25), 1_608_872_400);
+        // 2019-12-05T05:00:00Z
+        assert_eq!(secs(2019, 5), 1_575_522_000);
+
+        assert_eq!(
+            format_countdown(Duration::from_millis(3_723_500)),
+            "01:02:04"
+        );
+        assert_eq!(format_countdown(Duration::from_secs(59)), "00:00:59");
+    }
+}
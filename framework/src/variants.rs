@@ -0,0 +1,199 @@
+use crate::{
+    bench,
+    config::Config,
+    error::Error,
+    http::RequestThrottle,
+    input::{self, SessionKey},
+    options::Options,
+    report::{day_label, format_duration},
+    traits::{Day, Phase, PhaseResult},
+};
+use colored::Colorize;
+use std::time::Duration;
+
+/// The outcome of evaluating every implementation of every part of a day. If
+/// parsing fails, no parts are evaluated.
+#[derive(Debug)]
+pub struct VariantEvaluation {
+    pub parse: Phase<()>,
+    /// The phases of every implementation, per part.
+    pub parts: Vec<Vec<Phase<String>>>,
+}
+
+/// Whether a day has alternative implementations for any of its parts.
+pub fn has_alternatives(day: &dyn Day) -> bool {
+    day.variant_names().iter().any(|names| names.len() > 1)
+}
+
+/// Evaluates every implementation of a day once, or `bench_runs` times after warmup
+/// runs like `--bench`, as described by [`bench::run_repeatedly`].
+pub fn evaluate(day: &dyn Day, input: &str, bench_runs: Option<usize>) -> VariantEvaluation {
+    let variant_counts = day
+        .variant_names()
+        .iter()
+        .map(|names| names.len())
+        .collect::<Vec<_>>();
+    let evaluate = || {
+        let mut parse = None;
+        let mut phases = Vec::new();
+        day.evaluate_variants(input.to_owned(), &mut |phase| {
+            match phase {
+                PhaseResult::Parse(phase) => parse = Some(phase),
+                PhaseResult::Part(phase) => phases.push(phase),
+            }
+            true
+        });
+        let parse = parse.expect("parser was not evaluated");
+
+        let mut phases = phases.into_iter();
+        let parts = variant_counts
+            .iter()
+            .map(|&count| phases.by_ref().take(count).collect::<Vec<_>>())
+            .filter(|part| !part.is_empty())
+            .collect();
+        VariantEvaluation { parse, parts }
+    };
+    let (evaluation, _) = bench::run_repeatedly(
+        bench_runs.unwrap_or(1),
+        bench_runs.is_some(),
+        evaluate,
+        |evaluation| {
+            let mut durations = vec![&mut evaluation.parse.duration];
+            durations.extend(
+                evaluation
+                    .parts
+                    .iter_mut()
+                    .flatten()
+                    .map(|phase| &mut phase.duration),
+            );
+            durations
+        },
+    );
+    evaluation
+}
+
+/// Whether every implementation of a part succeeded with the same answer.
+pub fn is_consistent(part: &[Phase<String>]) -> bool {
+    match part.first().map(|phase| &phase.result) {
+        Some(Ok(answer)) => part
+            .iter()
+            .all(|phase| phase.result.as_ref().ok() == Some(answer)),
+        _ => false,
+    }
+}
+
+/// Evaluates every implementation of the parts of the runs that have any
+/// alternatives, and prints their answers and durations side by side, along
+/// with how much slower or faster they are than the main implementation.
+pub fn run(
+    config: &Config,
+    throttle: &mut RequestThrottle,
+    session_key: &mut SessionKey,
+    options: &Options,
+    runs: &[(u32, &'static dyn Day, Option<String>)],
+) -> Result<(), Error> {
+    const VALUE_ALIGNMENT: usize = 16;
+
+    let mut inconsistent = 0;
+    let mut evaluated = 0;
+    for (year, day, profile) in runs {
+        if !has_alternatives(*day) {
+            continue;
+        }
+        let input = input::load_input(
+            config,
+            throttle,
+            session_key,
+            options,
+            *year,
+            day.nr(),
+            profile.as_deref(),
+        )?;
+        let evaluation = evaluate(*day, &input, options.bench);
+        evaluated += 1;
+
+        let mut label = day_label(*year, config.year, day.nr());
+        if let Some(profile) = profile {
+            label = format!("{} [{}]", label, profile);
+        }
+        println!(
+            "{} (parse {} ms)",
+            label.bright_blue(),
+            format_duration(evaluation.parse.duration).bright_white()
+        );
+        if let Err(err) = &evaluation.parse.result {
+            println!("  {}", err.to_string().bright_red().bold());
+            inconsistent += 1;
+            continue;
+        }
+
+        let name_width = evaluation
+            .parts
+            .iter()
+            .flatten()
+            .map(|phase| phase.name.len())
+            .max()
+            .unwrap_or(0);
+        for part in &evaluation.parts {
+            let consistent = is_consistent(part);
+            if !consistent {
+                inconsistent += 1;
+            }
+            let main = &part[0];
+            for phase in part {
+                let value = match &phase.result {
+                    Ok(value) if consistent => value.bright_white().bold(),
+                    Ok(value) => value.bright_red().bold(),
+                    Err(err) => err.to_string().bright_red().bold().underline(),
+                };
+                let mut duration = format!("{} ms", format_duration(phase.duration));
+                if !std::ptr::eq(phase, main) && main.duration > Duration::default() {
+                    let ratio = phase.duration.as_secs_f64() / main.duration.as_secs_f64();
+                    duration = format!("{}, {:.2}x", duration, ratio);
+                }
+                println!(
+                    "  {:<name_width$} {:>value_width$} ({})",
+                    phase.name.bright_green(),
+                    value,
+                    duration.white(),
+                    name_width = name_width,
+                    value_width = VALUE_ALIGNMENT
+                );
+            }
+        }
+    }
+
+    if evaluated == 0 {
+        println!("none of the selected days have alternative implementations");
+    }
+    if inconsistent > 0 {
+        return Err(Error::InconsistentVariants(inconsistent));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn phase(result: Result<&str, Error>) -> Phase<String> {
+        Phase {
+            name: "pt1",
+            result: result.map(str::to_owned),
+            duration: Duration::default(),
+            allocations: None,
+        }
+    }
+
+    #[test]
+    fn consistency() {
+        assert!(is_consistent(&[phase(Ok("12"))]));
+        assert!(is_consistent(&[phase(Ok("12")), phase(Ok("12"))]));
+        assert!(!is_consistent(&[phase(Ok("12")), phase(Ok("13"))]));
+        assert!(!is_consistent(&[
+            phase(Ok("12")),
+            phase(Err(Error::NoSolution))
+        ]));
+        assert!(!is_consistent(&[phase(Err(Error::NoSolution))]));
+    }
+}
@@ -0,0 +1,268 @@
+use crate::{
+    config::Config,
+    error::Error,
+    http::RequestThrottle,
+    input::{self, SessionKey},
+    options::Options,
+    puzzle,
+    report::{DayReport, Reporter},
+    traits::{Day, Evaluation},
+};
+use arrayvec::ArrayVec;
+use colored::Colorize;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+/// How often the watched files are checked for changes.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The answer of a part, or `None` if it failed.
+type Answer = (&'static str, Option<String>);
+
+/// A file that is evaluated by a day, either its input or one of its examples.
+struct Target<'r> {
+    year: u32,
+    day: &'static dyn Day,
+    profile: &'r Option<String>,
+    example: Option<usize>,
+    path: PathBuf,
+}
+
+/// Evaluates the runs, and evaluates them again whenever their inputs or the
+/// examples of their days change, until the process is interrupted. Changes to
+/// the source of a day require a rebuild, and are not picked up.
+pub fn run(
+    config: &Config,
+    throttle: &mut RequestThrottle,
+    session_key: &mut SessionKey,
+    options: &Options,
+    reporter: &mut dyn Reporter,
+    runs: &[(u32, &'static dyn Day, Option<String>)],
+) -> Result<(), Error> {
+    let mut modified = HashMap::<PathBuf, Option<SystemTime>>::new();
+    let mut previous_answers = HashMap::<PathBuf, Vec<Answer>>::new();
+    let mut is_first_poll = true;
+    loop {
+        let targets = list_targets(config, options, runs)?;
+        for target in &targets {
+            let last_modified = get_modified(&target.path);
+            match modified.get(&target.path) {
+                Some(time) if *time == last_modified => continue,
+                Some(_) => println!("{} changed", target.path.display()),
+                None if !is_first_poll => println!("{} added", target.path.display()),
+                None => {}
+            }
+
+            match evaluate_target(config, throttle, session_key, options, reporter, target) {
+                Ok(answers) => {
+                    if let Some(previous) = previous_answers.get(&target.path) {
+                        for line in diff_answers(previous, &answers) {
+                            println!("  {}", line);
+                        }
+                    }
+                    previous_answers.insert(target.path.clone(), answers);
+                }
+                Err(err) => eprintln!("{}: {}", target.path.display(), err),
+            }
+            // Recorded after the evaluation, so that downloaded inputs are not
+            // seen as changed, and failed downloads are not retried until the
+            // file changes.
+            modified.insert(target.path.clone(), get_modified(&target.path));
+        }
+
+        if is_first_poll {
+            println!(
+                "{}",
+                format!(
+                    "watching {} file(s) for changes, press Ctrl-C to stop",
+                    targets.len()
+                )
+                .bright_white()
+            );
+            is_first_poll = false;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Lists the input of every run, followed by the examples of its day, which
+/// are listed again on every poll, so that new examples are picked up.
+fn list_targets<'r>(
+    config: &Config,
+    options: &Options,
+    runs: &'r [(u32, &'static dyn Day, Option<String>)],
+) -> Result<Vec<Target<'r>>, Error> {
+    let mut targets = Vec::new();
+    for (index, (year, day, profile)) in runs.iter().enumerate() {
+        let (year, day) = (*year, *day);
+        let path = match &options.input {
+            Some(path) => path.clone(),
+            None => input::get_day_input_path(config, profile.as_deref(), year, day.nr()),
+        };
+        targets.push(Target {
+            year,
+            day,
+            profile,
+            example: None,
+            path,
+        });
+
+        // Every profile shares the examples of a day.
+        let is_first_run_of_day = runs[..index]
+            .iter()
+            .all(|(other_year, other_day, _)| (*other_year, other_day.nr()) != (year, day.nr()));
+        if is_first_run_of_day {
+            for (example, path) in puzzle::list_examples(config, year, day.nr())? {
+                targets.push(Target {
+                    year,
+                    day,
+                    profile: &None,
+                    example: Some(example),
+                    path,
+                });
+            }
+        }
+    }
+    Ok(targets)
+}
+
+fn get_modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Evaluates a day on the file of a target, and reports the results. Only
+/// inputs are compared against the recorded answers.
+fn evaluate_target(
+    config: &Config,
+    throttle: &mut RequestThrottle,
+    session_key: &mut SessionKey,
+    options: &Options,
+    reporter: &mut dyn Reporter,
+    target: &Target,
+) -> Result<Vec<Answer>, Error> {
+    let day_nr = target.day.nr();
+    let (input, profile) = match target.example {
+        Some(example) => {
+            let mut input = input::read_input_file(config, &target.path)?;
+            if input.ends_with('\n') {
+                input.pop();
+            }
+            (input, Some(format!("example {}", example)))
+        }
+        None => {
+            let input = input::load_input(
+                config,
+                throttle,
+                session_key,
+                options,
+                target.year,
+                day_nr,
+                target.profile.as_deref(),
+            )?;
+            (input, target.profile.clone())
+        }
+    };
+
+    let (evaluation, stats) = crate::evaluate_day(target.day, input, options);
+    let verdicts = match target.example {
+        Some(_) => ArrayVec::new(),
+        None => crate::check_answers(
+            config,
+            options,
+            target.year,
+            day_nr,
+            profile.as_deref(),
+            &evaluation,
+        )?,
+    };
+    let answers = get_answers(&evaluation);
+    reporter.report_day(&DayReport {
+        year: target.year,
+        day_nr,
+        profile,
+        evaluation,
+        stats,
+        verdicts,
+    });
+    Ok(answers)
+}
+
+fn get_answers(evaluation: &Evaluation) -> Vec<Answer> {
+    evaluation
+        .parts
+        .iter()
+        .map(|part| (part.name, part.result.as_ref().ok().cloned()))
+        .collect()
+}
+
+/// Describes how the answers of each part changed since the previous
+/// evaluation. Parts that are missing, because parsing failed, count as failed.
+fn diff_answers(previous: &[Answer], current: &[Answer]) -> Vec<String> {
+    let find = |answers: &[Answer], name: &str| {
+        answers
+            .iter()
+            .find(|(answer_name, _)| *answer_name == name)
+            .and_then(|(_, answer)| answer.clone())
+    };
+    let describe = |answer: Option<String>| answer.unwrap_or_else(|| "failed".to_owned());
+
+    let mut names = current.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+    for (name, _) in previous {
+        if !names.contains(name) {
+            names.push(name);
+        }
+    }
+    let changes = names
+        .into_iter()
+        .filter_map(|name| {
+            let (previous, current) = (find(previous, name), find(current, name));
+            if previous == current {
+                None
+            } else {
+                Some(format!(
+                    "{}: {} -> {}",
+                    name,
+                    describe(previous),
+                    describe(current)
+                ))
+            }
+        })
+        .collect::<Vec<_>>();
+    if changes.is_empty() {
+        vec!["answers unchanged".to_owned()]
+    } else {
+        changes
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn answer_diffs() {
+        let previous = vec![("pt1", Some("12".to_owned())), ("pt2", None)];
+        assert_eq!(
+            diff_answers(&previous, &previous),
+            vec!["answers unchanged"]
+        );
+        assert_eq!(
+            diff_answers(
+                &previous,
+                &[
+                    ("pt1", Some("13".to_owned())),
+                    ("pt2", Some("4".to_owned()))
+                ]
+            ),
+            vec!["pt1: 12 -> 13", "pt2: failed -> 4"]
+        );
+        assert_eq!(diff_answers(&previous, &[]), vec!["pt1: 12 -> failed"]);
+    }
+}
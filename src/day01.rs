@@ -69,9 +69,9 @@ standard_tests!(
         "1721\n979\n366\n299\n675\n1456" => vec![299, 366, 675, 979, 1456, 1721]
     ]
     pt1 [
-        "1721\n979\n366\n299\n675\n1456" => 514579
+        &example(2020, 1, 0) => 514579
     ]
     pt2 [
-        "1721\n979\n366\n299\n675\n1456" => 241861950
+        &example(2020, 1, 0) => 241861950
     ]
 );
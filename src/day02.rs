@@ -16,18 +16,18 @@ fn is_valid_pt1((policy, input): &Input) -> bool {
     policy.minimum <= count && count <= policy.maximum
 }
 
-fn is_valid_pt2((policy, input): &Input) -> Result<bool> {
+fn is_valid_pt2((policy, input): &Input) -> bool {
     let at2 = input.as_bytes()[policy.maximum - 1] as char;
     let at1 = input.as_bytes()[policy.minimum - 1] as char;
-    Ok((at1 == policy.character) != (at2 == policy.character))
+    (at1 == policy.character) != (at2 == policy.character)
 }
 
 pub fn pt1(input: &[Input]) -> usize {
     input.iter().count_if(is_valid_pt1)
 }
 
-pub fn pt2(input: &[Input]) -> Result<usize> {
-    input.iter().count_if_res(is_valid_pt2)
+pub fn pt2(input: &[Input]) -> usize {
+    input.iter().count_if(is_valid_pt2)
 }
 
 pub fn parse(input: &str) -> Result<Vec<Input>> {
@@ -8,15 +8,15 @@ fn find_first_invalid_number<const PREAMBLE_PLUS_ONE: usize>(input: &[u64]) -> O
         .array_windows()
         .filter_map(|window: &[u64; PREAMBLE_PLUS_ONE]| {
             let target = window[PREAMBLE_PLUS_ONE - 1];
-            if window[0..PREAMBLE_PLUS_ONE - 2]
+            let is_sum = window[0..PREAMBLE_PLUS_ONE - 2]
                 .iter()
                 .enumerate()
                 .any(|(i, &x)| {
                     window[i + 1..PREAMBLE_PLUS_ONE - 1]
                         .iter()
                         .any(|&y| x + y == target)
-                })
-            {
+                });
+            if is_sum {
                 None
             } else {
                 Some(target)
@@ -38,12 +38,14 @@ impl std::fmt::Display for Set {
 /// Computes the intersection for the set.
 /// I have no idea what the time complexity is of finding the offset, but I'm
 /// sure there's some mathematically way to compute it in constant time. However
-/// this is basically instant in practice.
-fn intersect_sets(a: Set, b: Set) -> Set {
+/// this is basically instant in practice. For inconsistent moduli, there is no
+/// intersection and this never terminates, unless it is cancelled.
+fn intersect_sets(a: Set, b: Set) -> Result<Set> {
     let modulo = num::integer::lcm(a.modulo, b.modulo);
     let mut a_value = a.offset;
     let mut b_value = b.offset;
-    loop {
+    for iteration in 0usize.. {
+        cancel::check_every(iteration)?;
         match a_value.cmp(&b_value) {
             std::cmp::Ordering::Less => {
                 a_value += a.modulo * ((b_value - a_value) / a.modulo).max(1)
@@ -54,13 +56,13 @@ fn intersect_sets(a: Set, b: Set) -> Set {
             std::cmp::Ordering::Equal => break,
         }
     }
-    Set {
+    Ok(Set {
         modulo,
         offset: a_value,
-    }
+    })
 }
 
-pub fn pt2((_, bus_lines): &(u64, Vec<Option<u64>>)) -> u64 {
+pub fn pt2((_, bus_lines): &(u64, Vec<Option<u64>>)) -> Result<u64> {
     // Lets say the input lines are:
     // 2, 3, x, 7
     // Then the output `t` must satisfy these constraints:
@@ -99,8 +101,8 @@ pub fn pt2((_, bus_lines): &(u64, Vec<Option<u64>>)) -> u64 {
                 None
             }
         })
-        .fold(Set::default(), |acc, value| intersect_sets(acc, value))
-        .offset
+        .try_fold(Set::default(), intersect_sets)
+        .map(|set| set.offset)
 }
 
 pub fn parse(input: &str) -> Result<(u64, Vec<Option<u64>>)> {
@@ -20,7 +20,7 @@ pub struct Input<'s> {
     nearby_tickets: Vec<Ticket>,
 }
 
-fn get_valid_number(fields: &Vec<Field>) -> BitArray<LocalBits, [u64; 16]> {
+fn get_valid_number(fields: &[Field]) -> BitArray<LocalBits, [u64; 16]> {
     let mut valid_numbers = BitArray::<LocalBits, [u64; 16]>::default();
     for field in fields {
         valid_numbers[field.lower.clone()].set_all(true);
@@ -21,9 +21,7 @@ pub fn pt1(cells: &HashSet<i32x2>) -> usize {
             *accumulator.get_mut(&cell).unwrap() -= 1;
         }
         for (&cell, &neighbor_count) in &accumulator {
-            if (neighbor_count == 2 || neighbor_count == 3)
-                && (neighbor_count == 3 || prev_cells.contains(&cell))
-            {
+            if neighbor_count == 3 || (neighbor_count == 2 && prev_cells.contains(&cell)) {
                 cells.insert(cell);
             }
         }
@@ -63,9 +61,7 @@ pub fn pt2(cells: &HashSet<i32x2>) -> usize {
             *accumulator.get_mut(&cell).unwrap() -= 1;
         }
         for (&cell, &neighbor_count) in &accumulator {
-            if (neighbor_count == 2 || neighbor_count == 3)
-                && (neighbor_count == 3 || prev_cells.contains(&cell))
-            {
+            if neighbor_count == 3 || (neighbor_count == 2 && prev_cells.contains(&cell)) {
                 cells.insert(cell);
             }
         }
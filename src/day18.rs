@@ -11,7 +11,7 @@ fn value(input: &str) -> framework::parser::IResult<u64> {
         )));
     }
     let b = input.as_bytes()[0];
-    if b < b'1' || b > b'9' {
+    if !(b'1'..=b'9').contains(&b) {
         return Err(nom::Err::Error(AocParseError(
             input,
             AocErrorKind::TakeUnsigned(TakeIntErrorKind::InvalidCharacter),
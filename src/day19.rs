@@ -44,7 +44,7 @@ fn matches_rule(rules: &Rules, rule_index: u32, input: &str) -> bool {
     ) -> bool {
         match &rules[&rule_index] {
             &Rule::Letter(c) => {
-                if input.chars().next() == Some(c) {
+                if input.starts_with(c) {
                     continuation(&input[1..])
                 } else {
                     false
@@ -61,7 +61,7 @@ fn matches_rule(rules: &Rules, rule_index: u32, input: &str) -> bool {
         }
     }
     visit(rules, rule_index, input, &mut |remainder| {
-        remainder.len() == 0
+        remainder.is_empty()
     })
 }
 
@@ -96,7 +96,7 @@ pub fn parse(input: &str) -> Result<Input> {
     use framework::parser::*;
     let letter_rule = map(
         preceded(char('"'), terminated(anychar, char('"'))),
-        |c: char| Rule::Letter(c),
+        Rule::Letter,
     );
     fn space_take_u32(input: &str) -> IResult<u32> {
         preceded(char(' '), take_u32)(input)
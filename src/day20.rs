@@ -89,6 +89,7 @@ const fn get_all_borders(image: Image) -> [u16; 4] {
 }
 
 #[rustfmt::skip]
+#[allow(clippy::eq_op, clippy::identity_op, clippy::zero_prefixed_literal)]
 #[inline]
 const fn get_border(image: Image, direction: Direction) -> u16 {
     const BITS_10: u128 = 0b1111111111;
@@ -122,6 +123,20 @@ const fn get_border(image: Image, direction: Direction) -> u16 {
     }
 }
 
+#[allow(clippy::identity_op)]
+#[inline]
+const fn trim_border(image: Image) -> u64 {
+    const BITS_8: u128 = 0b11111111;
+    (00 | ((image >> 11 & BITS_8) << 0x00)
+        | ((image >> 21 & BITS_8) << 0x08)
+        | ((image >> 31 & BITS_8) << 0x10)
+        | ((image >> 41 & BITS_8) << 0x18)
+        | ((image >> 51 & BITS_8) << 0x20)
+        | ((image >> 61 & BITS_8) << 0x28)
+        | ((image >> 71 & BITS_8) << 0x30)
+        | ((image >> 81 & BITS_8) << 0x38)) as u64
+}
+
 impl Direction {
     #[inline]
     const fn rotate_90(self) -> Direction {
@@ -415,17 +430,7 @@ pub fn pt2(tiles: &[Tile]) -> Result<usize> {
     // Trim the border from the images
     let images = images
         .into_iter()
-        .map(|src| {
-            const BITS_8: u128 = 0b11111111;
-            (00 | ((src >> 11 & BITS_8) << 0x00)
-                | ((src >> 21 & BITS_8) << 0x08)
-                | ((src >> 31 & BITS_8) << 0x10)
-                | ((src >> 41 & BITS_8) << 0x18)
-                | ((src >> 51 & BITS_8) << 0x20)
-                | ((src >> 61 & BITS_8) << 0x28)
-                | ((src >> 71 & BITS_8) << 0x30)
-                | ((src >> 81 & BITS_8) << 0x38)) as u64
-        })
+        .map(trim_border)
         .collect::<Vec<_>>();
 
     let big_image_size = tile_size * 8;
@@ -8,8 +8,11 @@ pub struct IngredientList<'s> {
     allergens: HashSet<&'s str>,
 }
 
+// The ingredient lists, and every allergen with the ingredient containing it
+type Input<'s> = (Vec<IngredientList<'s>>, Vec<(&'s str, &'s str)>);
+
 pub fn pt1(
-    (ingredient_lists, known_allergens): &(Vec<IngredientList>, Vec<(&str, &str)>),
+    (ingredient_lists, known_allergens): &Input,
 ) -> Result<usize> {
     let known_ingredients = known_allergens
         .iter()
@@ -21,7 +24,7 @@ pub fn pt1(
         .count_if(|&ingredient| !known_ingredients.contains(ingredient)))
 }
 
-pub fn pt2((_, known_allergens): &(Vec<IngredientList>, Vec<(&str, &str)>)) -> String {
+pub fn pt2((_, known_allergens): &Input) -> String {
     known_allergens
         .iter()
         .sorted_by_key(|(allergen, _)| *allergen)
@@ -52,7 +55,7 @@ pub fn parse(input: &str) -> Result<Vec<IngredientList>> {
     separated_list1(char('\n'), ingredient_list)(input).into_result()
 }
 
-pub fn parse_and_process(input: &str) -> Result<(Vec<IngredientList>, Vec<(&str, &str)>)> {
+pub fn parse_and_process(input: &str) -> Result<Input> {
     let ingredient_lists = parse(input)?;
 
     let mut pending_allergens = HashMap::<&str, usize>::new();
@@ -89,9 +89,9 @@ pub fn parse(input: &str) -> Result<(Deck, Deck)> {
     }
     let decks = pair(deck, preceded(tag("\n\n"), deck))(input).into_result()?;
     if decks.0.len() == decks.1.len() {
-        return Ok(decks);
+        Ok(decks)
     } else {
-        return Err(Error::InvalidInput("different deck sizes"));
+        Err(Error::InvalidInput("different deck sizes"))
     }
 }
 
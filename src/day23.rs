@@ -2,7 +2,7 @@ use std::unreachable;
 
 use crate::prelude::*;
 
-day!(23, parse => pt1, pt2);
+day!(23, parse => pt1 = [pt1, pt1_original], pt2);
 
 const CUP_MASK: u64 = 0xfffffffff;
 
@@ -48,8 +48,8 @@ impl std::fmt::Debug for Cups {
 impl std::fmt::Display for Cups {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut chars = [0; 9];
-        for i in 0..9 {
-            chars[i] = ((self.0 >> (i * 4)) & 0xf) as u8 + b'1';
+        for (i, char) in chars.iter_mut().enumerate() {
+            *char = ((self.0 >> (i * 4)) & 0xf) as u8 + b'1';
         }
         f.write_str(unsafe { std::str::from_utf8_unchecked(&chars) })
     }
@@ -69,10 +69,9 @@ impl std::ops::Index<usize> for Cups {
     }
 }
 
-#[allow(dead_code)]
 impl Cups {
     fn rot_cw(self, mut steps: u8) -> Cups {
-        steps = steps % 9;
+        steps %= 9;
         Cups((self.0 << (steps * 4) | self.0 >> (36 - steps * 4)) & CUP_MASK)
     }
     fn rot_ccw(self, steps: u8) -> Cups {
@@ -82,7 +81,6 @@ impl Cups {
 
 // Original implementation of pt1, which actually updated the entire cups with
 // bitwise logic.
-#[allow(dead_code)]
 pub fn pt1_original(input: &Cups) -> Result<String> {
     let mut cups = *input;
 
@@ -166,9 +164,9 @@ fn pt1(input: &Cups) -> String {
     let links = pts::<9, 100>(input);
     let mut res = [0u8; 8];
     let mut curr = 0;
-    for i in 0..8 {
+    for char in &mut res {
         curr = links[curr];
-        res[i] = curr as u8 + b'1';
+        *char = curr as u8 + b'1';
     }
     std::str::from_utf8(&res).unwrap().to_owned()
 }
@@ -187,5 +185,6 @@ pub fn parse(input: &str) -> Result<Cups> {
 standard_tests!(
     parse []
     pt1 [ "389125467" => "67384529" ]
+    pt1_original [ "389125467" => "67384529" ]
     pt2 [ "389125467" => 149245887792 ]
 );
@@ -25,10 +25,11 @@ impl Iterator for Transformer {
     }
 }
 
-pub fn pt1(&(mut card, mut door): &(u64, u64)) -> u64 {
+pub fn pt1(&(mut card, mut door): &(u64, u64)) -> Result<u64> {
     let mut transformer = Transformer::default().enumerate();
     loop {
-        let (_, nr) = transformer.next().unwrap();
+        let (count, nr) = transformer.next().unwrap();
+        cancel::check_every(count)?;
 
         if nr == card || nr == door {
             if nr == door {
@@ -42,15 +43,15 @@ pub fn pt1(&(mut card, mut door): &(u64, u64)) -> u64 {
         if nr == door {
             break count;
         }
+        cancel::check_every(count)?;
     };
 
-    Transformer {
+    Ok(Transformer {
         current: 1,
         subject_number: card,
     }
-    .skip(skip_count)
-    .next()
-    .unwrap()
+    .nth(skip_count)
+    .unwrap())
 }
 
 pub fn pt2(_: &(u64, u64)) -> &'static str {
@@ -1,7 +1,7 @@
 pub use ahash::{AHashMap as HashMap, AHashSet as HashSet};
 pub use data_structures::{vec2, vec3, vector::*};
 pub use framework::{
-    day,
+    cancel, day,
     error::{Error, Result},
     iter::*,
     parser, standard_tests,